@@ -1,58 +1,378 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
 declare_id!("CyJDfKuJ7aAF86dJifrKXBWLLrT2TcmoqSVvqgTJ9FR6");
 
+//most nodes `roster` returns, keeping the summaries within the 1024-byte return data limit
+pub const MAX_ROSTER_SIZE: usize = 24;
+
+//most distinct slashers a node records, bounding Node's size
+pub const MAX_SLASH_WITNESSES: usize = 5;
+
+//schema version carried by every event; bump whenever an event's fields change
+pub const EVENT_VERSION: u8 = 2;
+
+//longest reveal window an oracle may configure, so collateral can't be locked up indefinitely
+pub const MAX_REVEAL_DURATION: i64 = 30 * 24 * 60 * 60;
+
+//most that extend_reveal and pauses together may push back one round's reveal deadline
+pub const MAX_REVEAL_EXTENSION: i64 = MAX_REVEAL_DURATION;
+
+//how long after the reveal deadline an unresolved oracle may be drained by its authority
+pub const EMERGENCY_TIMEOUT: i64 = 180 * 24 * 60 * 60;
+
+//prefixes every commitment preimage, keeping them distinct from other hashes a key may sign or commit to
+pub const COMMITMENT_DOMAIN: &[u8] = b"binary_oracle_v1";
+
+//layout version written by export_state; import_state also converts version 1 exports, see LegacyOracleV1
+pub const EXPORT_VERSION: u8 = 2;
+
 #[program]
 pub mod binary_oracle {
     use super::*;
 
-    pub fn initialize(
-        ctx: Context<Initialize>, 
-        collateral: u64, 
-        reveal_duration: i64, 
-        max_nodes: u64
-    ) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>, mut config: OracleConfig, question_hash: [u8; 32]) -> Result<()> {
+        // The oracle must commit to the question it answers, or its resolution bit means nothing
+        require!(question_hash != [0u8; 32], ErrorCode::EmptyQuestionHash);
+
+        // Without collateral, a committee or a reveal window the round can never run or punish anyone
+        require!(config.collateral > 0, ErrorCode::InvalidCollateral);
+        require!(config.max_nodes > 0, ErrorCode::InvalidMaxNodes);
+        require!(config.min_nodes <= config.max_nodes, ErrorCode::InvalidMinNodes);
+        require!(
+            config.reveal_duration > 0 && config.reveal_duration <= MAX_REVEAL_DURATION,
+            ErrorCode::InvalidRevealDuration
+        );
+
+        // Optionally reject reveal windows too short for the committee size (0 disables)
+        if config.min_reveal_seconds_per_node > 0 {
+            let min_reveal_duration = i64::try_from(config.max_nodes)
+                .ok()
+                .and_then(|nodes| nodes.checked_mul(config.min_reveal_seconds_per_node));
+            require!(
+                min_reveal_duration.map_or(false, |min| config.reveal_duration >= min),
+                ErrorCode::RevealWindowTooShort
+            );
+        }
+        // Ordered reveals need at least a one-second slot per node
+        if config.ordered_reveal {
+            require!(
+                i64::try_from(config.max_nodes).map_or(false, |nodes| config.reveal_duration >= nodes),
+                ErrorCode::RevealWindowTooShort
+            );
+        }
+
+        require!(config.loser_rebate_bps <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(config.late_penalty_bps <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(config.late_reveal_penalty_bps <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(config.slasher_reward_bps <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(config.fee_bps <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(config.quorum_bps <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(config.slash_bps <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(
+            config.late_reveal_window >= 0 && config.late_reveal_window <= config.reveal_duration,
+            ErrorCode::InvalidDeadline
+        );
+        require!(
+            config.required_witnesses as usize <= MAX_SLASH_WITNESSES,
+            ErrorCode::TooManyWitnesses
+        );
+        require!(
+            config.num_bits <= 64 && config.min_matching_bits <= config.num_bits.max(1),
+            ErrorCode::InvalidBitCount
+        );
+        require!(
+            config.outcome_count != 1 && (config.outcome_count <= 2 || config.num_bits == 0),
+            ErrorCode::InvalidOutcomeCount
+        );
+        require!(
+            !config.stake_weighted || (config.num_bits == 0 && config.outcome_count <= 2),
+            ErrorCode::StakeWeightedBinaryOnly
+        );
+
+        // Fund the keeper bounty, the transition bonus and the authority's liveness bond up front.
+        // The bond only backs an authority that resolves, so other oracles don't post one
+        if !config.authority_resolves {
+            config.resolution_bond = 0;
+        }
+        let prefunded = config
+            .resolve_bounty
+            .checked_add(config.transition_bonus)
+            .and_then(|sum| sum.checked_add(config.resolution_bond))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        if prefunded > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: ctx.accounts.oracle.to_account_info(),
+                    },
+                ),
+                prefunded,
+            )?;
+        }
+
         let oracle = &mut ctx.accounts.oracle;
         oracle.authority = ctx.accounts.authority.key();
-        oracle.collateral = collateral;
+        oracle.config = config;
+        // Protocol fees go to the authority unless another recipient is configured
+        if oracle.config.fee_recipient == Pubkey::default() {
+            oracle.config.fee_recipient = oracle.authority;
+        }
+        oracle.question_hash = question_hash;
         oracle.is_resolved = false;
         oracle.resolution_bit = false;
+        oracle.resolution_mask = 0;
+        oracle.resolution_outcome = 0;
         oracle.phase = Phase::Precommit;
         oracle.reveal_end_time = 0;
-        oracle.reveal_duration = reveal_duration;
-        oracle.max_nodes = max_nodes;
         oracle.total_nodes = 0;
         oracle.committed_nodes = 0;
+        oracle.revealed_nodes = 0;
+        oracle.tallied_nodes = 0;
+        oracle.tally_true_votes = 0;
+        oracle.tally_false_votes = 0;
+        oracle.tally_true_weight = 0;
+        oracle.tally_false_weight = 0;
+        oracle.tally_authorities = 0;
+        oracle.created_at = Clock::get()?.unix_timestamp;
+        oracle.round = 0;
+        oracle.slashed_this_round = 0;
+        oracle.reward_per_node = 0;
+        oracle.loser_rebate = 0;
+        oracle.consensus_stake = 0;
+        oracle.stake_reward_pool = 0;
+        oracle.reward_pool = 0;
+        oracle.refund_share = 0;
+        oracle.bounty_pool = 0;
+        oracle.slashed_pool = 0;
+        oracle.commit_end_time = 0;
+        oracle.transition_node = None;
+        oracle.reveal_start_slot = 0;
+        oracle.reveal_started_at = 0;
+        oracle.reveal_seed = [0; 32];
+        oracle.reveal_extension = 0;
+        oracle.paused_duration = 0;
+        oracle.paused = false;
+        oracle.paused_at = 0;
+        oracle.extensions_used = 0;
+        oracle.pending_authority = None;
+        oracle.request_count = 0;
+        oracle.adjudicated_bit = None;
+        oracle.slashed_nodes = 0;
+        oracle.final_true_votes = 0;
+        oracle.final_false_votes = 0;
+        oracle.final_slashed = 0;
+        oracle.migrated_from = None;
+        oracle.resolved_at = 0;
+        oracle.dispute = None;
+        oracle.finalized_at = None;
+        oracle.dispute_end_time = 0;
+        oracle.revealed_nonces = Vec::new();
+        oracle.votes_snapshot = Vec::new();
+        oracle.mint = Pubkey::default();
+        oracle.vault = Pubkey::default();
+
+        emit!(OracleInitialized {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            authority: oracle.authority,
+            question_hash,
+        });
+        Ok(())
+    }
+
+    //switch a fresh oracle to collateral in `mint`, held in a vault owned by the oracle's vault PDA
+    pub fn init_token_vault(ctx: Context<InitTokenVault>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Precommit, ErrorCode::InvalidPhase);
+        require!(
+            oracle.total_nodes == 0 && !oracle.token_collateral(),
+            ErrorCode::CollateralModeLocked
+        );
+
+        oracle.mint = ctx.accounts.mint.key();
+        oracle.vault = ctx.accounts.vault.key();
         Ok(())
     }
 
     //join network during precommit or commit phase, post collateral
-    pub fn join_network(ctx: Context<JoinNetwork>) -> Result<()> {
+    pub fn join_network<'info>(
+        ctx: Context<'_, '_, 'info, 'info, JoinNetwork<'info>>,
+        stake: u64,
+    ) -> Result<()> {
         let oracle = &mut ctx.accounts.oracle;
         let node = &mut ctx.accounts.node;
         let node_authority = &ctx.accounts.node_authority;
 
+        // Gated oracles read the authority's reputation PDA from the first remaining account
+        check_join(
+            oracle,
+            &mut ctx.accounts.membership,
+            node_authority.key(),
+            ctx.remaining_accounts.first(),
+        )?;
+        require!(stake >= oracle.config.collateral, ErrorCode::InsufficientStake);
+
+        reset_node(node, node_authority.key(), oracle.key());
+
+        // With deferred funding the collateral arrives separately through fund_join, and stake is ignored
+        if oracle.config.deferred_funding {
+            node.state = NodeState::PendingJoin;
+            node.stake = 0;
+            return Ok(());
+        }
+
+        // Transfer the stake from node authority to oracle account; the authority is
+        // system-owned, so only the system program may debit it
+        if oracle.token_collateral() {
+            // Token accounts follow the reputation account when the oracle is gated
+            let offset = (oracle.config.min_reputation_to_join > 0) as usize;
+            let accounts = ctx.remaining_accounts.get(offset..).unwrap_or_default();
+            let ([owner_token, vault, _, token_program], _) = token_accounts(oracle, accounts)?;
+            token::transfer(
+                CpiContext::new(
+                    token_program,
+                    token::Transfer {
+                        from: owner_token,
+                        to: vault,
+                        authority: node_authority.to_account_info(),
+                    },
+                ),
+                stake,
+            )?;
+        } else {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: node_authority.to_account_info(),
+                        to: oracle.to_account_info(),
+                    },
+                ),
+                stake,
+            )?;
+        }
+
+        node.state = NodeState::Joined;
+        node.stake = stake;
+
+        oracle.total_nodes = oracle.total_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(NodeJoined {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            node: node.key(),
+            authority: node.authority,
+            stake,
+        });
+
+        Ok(())
+    }
+
+    //post the collateral for a pending join; any signer may fund, so wrapping programs can CPI in
+    pub fn fund_join(ctx: Context<FundJoin>) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        require!(!oracle.token_collateral(), ErrorCode::NativeCollateralOnly);
         require!(
             oracle.phase == Phase::Precommit || oracle.phase == Phase::Commit,
             ErrorCode::InvalidPhaseForJoining
         );
         require!(
-            oracle.total_nodes < oracle.max_nodes,
+            oracle.total_nodes < oracle.config.max_nodes,
             ErrorCode::MaxNodesReached
         );
 
-        // Transfer collateral from node authority to oracle account
-        let collateral = oracle.collateral;
-        **node_authority.to_account_info().try_borrow_mut_lamports()? -= collateral;
-        **oracle.to_account_info().try_borrow_mut_lamports()? += collateral;
+        let collateral = oracle.config.collateral;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.oracle.to_account_info(),
+                },
+            ),
+            collateral,
+        )?;
+
+        // Anything top_up_stake added while the join was pending stays on top of the collateral
+        let node = &mut ctx.accounts.node;
+        node.transition(NodeState::Joined)?;
+        node.stake = node.stake.checked_add(collateral).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.total_nodes = oracle.total_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(NodeJoined {
+            event_version: EVENT_VERSION,
+            oracle: node.oracle,
+            node: node.key(),
+            authority: node.authority,
+            stake: node.stake,
+        });
+
+        Ok(())
+    }
+
+    //add stake on top of the joining collateral, e.g. to meet a reveal stake requirement
+    pub fn top_up_stake(ctx: Context<TopUpStake>, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.oracle.token_collateral(), ErrorCode::NativeCollateralOnly);
+        require!(
+            matches!(ctx.accounts.oracle.phase, Phase::Precommit | Phase::Commit),
+            ErrorCode::InvalidPhase
+        );
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.oracle.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let node = &mut ctx.accounts.node;
+        node.stake = node.stake.checked_add(amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    //leave before committing, refunding stake and closing the node account. Token collateral
+    //comes back from the vault through the token accounts in remaining_accounts
+    pub fn leave_network<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LeaveNetwork<'info>>
+    ) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+
+        require!(
+            oracle.phase == Phase::Precommit || oracle.phase == Phase::Commit,
+            ErrorCode::InvalidPhase
+        );
+        require!(node.state == NodeState::Joined, ErrorCode::AlreadyCommitted);
+        node.transition(NodeState::Withdrawn)?;
+
+        let authority = ctx.accounts.authority.to_account_info();
+        pay_collateral(oracle, &authority, ctx.remaining_accounts, node.stake)?;
 
-        node.authority = node_authority.key();
-        node.vote_hash = None;
-        node.vote = None;
-        node.slashed = false;
+        oracle.total_nodes = oracle.total_nodes.checked_sub(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        ctx.accounts.membership.left_at = Some(Clock::get()?.unix_timestamp);
 
-        oracle.total_nodes += 1;
+        // The leaver may have been the last node the commit phase was waiting on
+        if oracle.phase == Phase::Commit
+            && oracle.total_nodes > 0
+            && all_committed(oracle.committed_nodes, oracle.total_nodes)?
+        {
+            start_reveal(oracle)?;
+        }
 
         Ok(())
     }
@@ -60,34 +380,193 @@ pub mod binary_oracle {
     //start the request (must be oracle authority)
     pub fn start_request(ctx: Context<StartRequest>) -> Result<()> {
         let oracle = &mut ctx.accounts.oracle;
+        require!(!oracle.is_resolved, ErrorCode::AlreadyResolved);
         require!(oracle.phase == Phase::Precommit, ErrorCode::InvalidPhase);
         require!(
             ctx.accounts.authority.key() == oracle.authority,
             ErrorCode::UnauthorizedAccess
         );
 
-        oracle.phase = Phase::Commit;
-        oracle.committed_nodes = 0;
+        open_commit(oracle)
+    }
+
+    //authority gives up on a commit phase that never completed; nodes then refund through claim_abandoned
+    pub fn cancel_request(ctx: Context<CancelRequest>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Commit, ErrorCode::InvalidPhase);
+        require!(oracle.config.commit_deadline > 0, ErrorCode::CommitDeadlineNotReached);
+
+        let deadline = oracle
+            .request_start_time
+            .checked_add(oracle.config.commit_deadline)
+            .ok_or(ErrorCode::InvalidDeadline)?;
+        require!(
+            Clock::get()?.unix_timestamp >= deadline,
+            ErrorCode::CommitDeadlineNotReached
+        );
+
+        // The round ends without a resolve, so the authority's liveness bond goes back here
+        let resolution_bond = oracle.config.resolution_bond;
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= resolution_bond;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += resolution_bond;
+
+        oracle.phase = Phase::Cancelled;
+        Ok(())
+    }
 
+    //force the reveal phase once commit_end_time passes, with whichever nodes committed
+    pub fn close_commit(ctx: Context<CloseCommit>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Commit, ErrorCode::InvalidPhase);
+        require!(oracle.config.commit_duration > 0, ErrorCode::CommitPhaseNotClosed);
+        require!(
+            Clock::get()?.unix_timestamp > oracle.commit_end_time,
+            ErrorCode::CommitPhaseNotClosed
+        );
+
+        start_reveal(oracle)
+    }
+
+    //rederive reveal_end_time from its stored components after any timing change
+    pub fn recompute_deadline(ctx: Context<RecomputeDeadline>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        oracle.recompute_deadline()
+    }
+
+    //halt or resume joins, votes and slashing; time spent paused during the reveal phase is added
+    //to the reveal deadline on resume, up to what MAX_REVEAL_EXTENSION still allows
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        if oracle.paused == paused {
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        if paused {
+            oracle.paused_at = now;
+        } else if oracle.phase == Phase::Reveal {
+            let elapsed = now
+                .checked_sub(oracle.paused_at.max(oracle.reveal_started_at))
+                .ok_or(ErrorCode::ArithmeticOverflow)?
+                .min(oracle.extension_room());
+            oracle.paused_duration = oracle
+                .paused_duration
+                .checked_add(elapsed)
+                .ok_or(ErrorCode::InvalidDeadline)?;
+            oracle.recompute_deadline()?;
+        }
+        oracle.paused = paused;
         Ok(())
     }
 
+    //push back the reveal deadline, e.g. when congestion kept nodes from revealing in time
+    pub fn extend_reveal(ctx: Context<ExtendReveal>, additional_seconds: i64) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            Clock::get()?.unix_timestamp <= oracle.reveal_end_time,
+            ErrorCode::RevealPhaseClosed
+        );
+        require!(additional_seconds > 0, ErrorCode::InvalidDeadline);
+        require!(
+            oracle.extensions_used < oracle.config.max_reveal_extensions,
+            ErrorCode::MaxExtensionsReached
+        );
+        require!(
+            additional_seconds <= oracle.extension_room(),
+            ErrorCode::RevealExtensionCapReached
+        );
+
+        oracle.reveal_extension = oracle
+            .reveal_extension
+            .checked_add(additional_seconds)
+            .ok_or(ErrorCode::InvalidDeadline)?;
+        oracle.extensions_used = oracle.extensions_used.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        oracle.recompute_deadline()
+    }
+
+    //permissionlessly start the request once the configured price feed crosses its threshold
+    pub fn try_trigger(ctx: Context<TryTrigger>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Precommit, ErrorCode::InvalidPhase);
+
+        let trigger = oracle.config.price_trigger.ok_or(ErrorCode::NoPriceTrigger)?;
+        let price_info = &ctx.accounts.price_account;
+        require!(price_info.key() == trigger.price_account, ErrorCode::PriceAccountMismatch);
+
+        let price = read_pyth_price(&price_info.try_borrow_data()?)?;
+        let crossed = if trigger.trigger_above {
+            price >= trigger.threshold
+        } else {
+            price <= trigger.threshold
+        };
+        require!(crossed, ErrorCode::PriceTriggerNotMet);
+
+        open_commit(oracle)
+    }
+
     //commit vote during commit phase
     pub fn commit(ctx: Context<Commit>, vote_hash: [u8; 32]) -> Result<()> {
         let oracle = &mut ctx.accounts.oracle;
         let node = &mut ctx.accounts.node;
 
+        require!(!oracle.paused, ErrorCode::Paused);
         require!(oracle.phase == Phase::Commit, ErrorCode::InvalidPhase);
-        require!(node.vote_hash.is_none(), ErrorCode::AlreadyCommitted);
+        require!(
+            !matches!(node.state, NodeState::Committed | NodeState::Revealed),
+            ErrorCode::AlreadyCommitted
+        );
+
+        // Commit fees are held apart from collateral and only ever fund the keeper bounty
+        let commit_fee = oracle.config.commit_fee;
+        if commit_fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: oracle.to_account_info(),
+                    },
+                ),
+                commit_fee,
+            )?;
+            oracle.bounty_pool = oracle.bounty_pool.checked_add(commit_fee).ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
 
+        node.transition(NodeState::Committed)?;
         node.vote_hash = Some(vote_hash);
-        oracle.committed_nodes += 1;
+        node.committed_at = Clock::get()?.unix_timestamp;
+        oracle.committed_nodes = oracle.committed_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(VoteCommitted {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            node: node.key(),
+        });
 
         // If all nodes have committed, start the reveal phase
-        if oracle.committed_nodes == oracle.total_nodes {
-            oracle.phase = Phase::Reveal;
-            let clock = Clock::get()?;
-            oracle.reveal_end_time = clock.unix_timestamp + oracle.reveal_duration;
+        if all_committed(oracle.committed_nodes, oracle.total_nodes)? {
+            start_reveal(oracle)?;
+            oracle.transition_node = Some(node.key());
+        }
+
+        Ok(())
+    }
+
+    //sit out the current round before committing; the node no longer holds up the reveal transition
+    pub fn declare_abstain(ctx: Context<DeclareAbstain>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+
+        require!(oracle.phase == Phase::Commit, ErrorCode::InvalidPhase);
+        require!(node.state == NodeState::Joined, ErrorCode::AlreadyCommitted);
+        node.transition(NodeState::Abstained)?;
+        oracle.total_nodes = oracle.total_nodes.checked_sub(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        // The abstainer may have been the last node the commit phase was waiting on
+        if oracle.total_nodes > 0 && all_committed(oracle.committed_nodes, oracle.total_nodes)? {
+            start_reveal(oracle)?;
         }
 
         Ok(())
@@ -98,191 +577,4016 @@ pub mod binary_oracle {
         let oracle = &mut ctx.accounts.oracle;
         let node = &mut ctx.accounts.node;
 
+        require!(!oracle.paused, ErrorCode::Paused);
+        require!(!oracle.is_resolved, ErrorCode::AlreadyResolved);
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            oracle.config.num_bits == 0 && !oracle.multi_outcome(),
+            ErrorCode::WrongRevealMode
+        );
+        // A Reveal phase without a deadline was never opened by start_reveal
+        require!(oracle.reveal_end_time != 0, ErrorCode::RevealNotStarted);
+        require!(Clock::get()?.unix_timestamp <= oracle.reveal_end_time, ErrorCode::RevealPhaseClosed);
+        require!(node.state != NodeState::Joined, ErrorCode::NotCommitted);
+        require!(node.state != NodeState::Revealed, ErrorCode::AlreadyRevealed);
+        check_reveal_slot(oracle, &node.key())?;
+
+        record_reveal(oracle, node, vote, &nonce)
+    }
+
+    //reveal several binary votes in one transaction. remaining_accounts are (node, node_authority)
+    //pairs in the order of `votes`, and every node authority must sign
+    pub fn batch_reveal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchReveal<'info>>,
+        votes: Vec<(bool, [u8; 32])>,
+    ) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+
+        require!(!oracle.paused, ErrorCode::Paused);
+        require!(!oracle.is_resolved, ErrorCode::AlreadyResolved);
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            oracle.config.num_bits == 0 && !oracle.multi_outcome(),
+            ErrorCode::WrongRevealMode
+        );
+        // A Reveal phase without a deadline was never opened by start_reveal
+        require!(oracle.reveal_end_time != 0, ErrorCode::RevealNotStarted);
+        require!(Clock::get()?.unix_timestamp <= oracle.reveal_end_time, ErrorCode::RevealPhaseClosed);
+        require!(
+            ctx.remaining_accounts.len() == votes.len() * 2,
+            ErrorCode::InvalidRemainingAccounts
+        );
+
+        for (pair, (vote, nonce)) in ctx.remaining_accounts.chunks(2).zip(votes.iter()) {
+            let mut node = oracle_node(oracle, &pair[0])?;
+            let node_authority = &pair[1];
+            require!(
+                node_authority.is_signer && node.authority == node_authority.key(),
+                ErrorCode::UnauthorizedAccess
+            );
+            require!(node.state != NodeState::Joined, ErrorCode::NotCommitted);
+            require!(node.state != NodeState::Revealed, ErrorCode::AlreadyRevealed);
+            check_reveal_slot(oracle, &node.key())?;
+
+            record_reveal(oracle, &mut node, *vote, nonce)?;
+            node.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    //reveal one answer per question as a bitmask, for oracles with num_bits > 0
+    pub fn reveal_mask(ctx: Context<Reveal>, mask: u64, nonce: [u8; 32]) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+
+        require!(!oracle.paused, ErrorCode::Paused);
+        require!(!oracle.is_resolved, ErrorCode::AlreadyResolved);
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(oracle.config.num_bits > 0, ErrorCode::WrongRevealMode);
+        // A Reveal phase without a deadline was never opened by start_reveal
+        require!(oracle.reveal_end_time != 0, ErrorCode::RevealNotStarted);
+        require!(Clock::get()?.unix_timestamp <= oracle.reveal_end_time, ErrorCode::RevealPhaseClosed);
+        require!(node.state != NodeState::Joined, ErrorCode::NotCommitted);
+        require!(node.state != NodeState::Revealed, ErrorCode::AlreadyRevealed);
+        require!(mask & !oracle.bits_mask() == 0, ErrorCode::InvalidReveal);
+        check_reveal_slot(oracle, &node.key())?;
+
+        let commitment = mask_commitment(oracle, &node.key(), mask, &nonce);
+        finish_reveal(oracle, node, commitment, &nonce, mask)
+    }
+
+    //reveal an outcome index, for oracles with outcome_count > 2
+    pub fn reveal_outcome(ctx: Context<Reveal>, outcome: u8, nonce: [u8; 32]) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+
+        require!(!oracle.paused, ErrorCode::Paused);
+        require!(!oracle.is_resolved, ErrorCode::AlreadyResolved);
         require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(oracle.multi_outcome(), ErrorCode::WrongRevealMode);
+        // A Reveal phase without a deadline was never opened by start_reveal
+        require!(oracle.reveal_end_time != 0, ErrorCode::RevealNotStarted);
         require!(Clock::get()?.unix_timestamp <= oracle.reveal_end_time, ErrorCode::RevealPhaseClosed);
-        require!(node.vote_hash.is_some(), ErrorCode::NotCommitted);
-        require!(node.vote.is_none(), ErrorCode::AlreadyRevealed);
+        require!(node.state != NodeState::Joined, ErrorCode::NotCommitted);
+        require!(node.state != NodeState::Revealed, ErrorCode::AlreadyRevealed);
+        require!(outcome < oracle.config.outcome_count, ErrorCode::InvalidReveal);
+        check_reveal_slot(oracle, &node.key())?;
+
+        let commitment = outcome_commitment(oracle, &node.key(), outcome, &nonce);
+        finish_reveal(oracle, node, commitment, &nonce, outcome as u64)
+    }
 
-        let calculated_hash = hash(&[&[vote as u8], &nonce[..]].concat()).to_bytes();
-        require!(calculated_hash == node.vote_hash.unwrap(), ErrorCode::InvalidReveal);
+    //opt in to auto_reveal by storing the vote sealed under a key escrowed with a keeper
+    pub fn submit_sealed_vote(
+        ctx: Context<SubmitSealedVote>,
+        ciphertext: [u8; 33],
+        key_hash: [u8; 32],
+    ) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
 
-        node.vote = Some(vote);
+        require!(
+            oracle.phase == Phase::Commit || oracle.phase == Phase::Reveal,
+            ErrorCode::InvalidPhase
+        );
+        require!(node.state == NodeState::Committed, ErrorCode::NotCommitted);
 
+        node.sealed_vote = Some(SealedVote { ciphertext, key_hash });
         Ok(())
     }
 
+    //permissionlessly reveal an offline node's sealed vote near the reveal deadline
+    pub fn auto_reveal(ctx: Context<AutoReveal>, key: [u8; 32]) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(!oracle.is_resolved, ErrorCode::AlreadyResolved);
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            oracle.config.num_bits == 0 && !oracle.multi_outcome(),
+            ErrorCode::WrongRevealMode
+        );
+        require!(oracle.reveal_end_time != 0, ErrorCode::RevealNotStarted);
+        require!(now <= oracle.reveal_end_time, ErrorCode::RevealPhaseClosed);
+        require!(
+            now >= oracle.reveal_end_time.saturating_sub(oracle.config.auto_reveal_window),
+            ErrorCode::AutoRevealNotOpen
+        );
+        require!(node.state != NodeState::Revealed, ErrorCode::AlreadyRevealed);
+        require!(node.state == NodeState::Committed, ErrorCode::NotCommitted);
+
+        let sealed = node.sealed_vote.ok_or(ErrorCode::NoSealedVote)?;
+        require!(hash(&key).to_bytes() == sealed.key_hash, ErrorCode::InvalidEscrowKey);
+
+        // Sealing is a plain XOR pad, so unsealing is the same operation
+        let plaintext = sealed_vote_ciphertext(&sealed.ciphertext, &key, &node.key());
+        let vote = match plaintext[0] {
+            0 => false,
+            1 => true,
+            _ => return err!(ErrorCode::InvalidReveal),
+        };
+        let nonce: [u8; 32] = plaintext[1..].try_into().unwrap();
+
+        record_reveal(oracle, node, vote, &nonce)
+    }
+
     //slash colluding node with proof of collusion
-    pub fn slash_colluding(ctx: Context<SlashColluding>, vote: bool, nonce: [u8; 32]) -> Result<()> {
+    pub fn slash_colluding<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SlashColluding<'info>>,
+        vote: bool,
+        nonce: [u8; 32],
+    ) -> Result<()> {
         let oracle = &mut ctx.accounts.oracle;
         let colluding_node = &mut ctx.accounts.colluding_node;
 
+        require!(!oracle.paused, ErrorCode::Paused);
+        require!(colluding_node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
         require!(oracle.phase == Phase::Commit, ErrorCode::InvalidPhase);
-        require!(colluding_node.vote_hash.is_some(), ErrorCode::NotCommitted);
+        // Only a node holding a live commitment can have leaked it
+        require!(colluding_node.state == NodeState::Committed, ErrorCode::NotCommitted);
+
+        let vote_hash = colluding_node.vote_hash.ok_or(ErrorCode::NotCommitted)?;
+        let calculated_hash = vote_commitment(oracle, &colluding_node.key(), vote, &nonce);
+        require!(calculated_hash == vote_hash, ErrorCode::InvalidCollusion);
+
+        // Each witness bonds its accusation; bonds are forfeited if the slash is overturned
+        let slash_bond = oracle.config.slash_bond;
+        if slash_bond > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.slasher.to_account_info(),
+                        to: oracle.to_account_info(),
+                    },
+                ),
+                slash_bond,
+            )?;
+        }
 
-        let calculated_hash = hash(&[&[vote as u8], &nonce[..]].concat()).to_bytes();
-        require!(calculated_hash == colluding_node.vote_hash.unwrap(), ErrorCode::InvalidCollusion);
+        // The slash only takes effect once enough distinct witnesses have submitted the proof
+        let slasher = ctx.accounts.slasher.key();
+        require!(
+            !colluding_node.slash_witnesses.contains(&slasher),
+            ErrorCode::DuplicateWitness
+        );
+        colluding_node.slash_witnesses.push(slasher);
+        if colluding_node.slash_witnesses.len() < oracle.config.required_witnesses as usize {
+            return Ok(());
+        }
 
-        // Transfer collateral from colluding node to oracle pool
-        let collateral = oracle.collateral;
-        **colluding_node.to_account_info().try_borrow_mut_lamports()? -= collateral;
-        **oracle.to_account_info().try_borrow_mut_lamports()? += collateral;
+        // The collateral already sits in the oracle; the slasher's cut of the seized part is paid
+        // now and the rest is earmarked for the consensus nodes. Any unseized remainder stays
+        // reclaimable by the node
+        let seized = oracle.slash_amount(colluding_node.stake);
+        let slasher_reward = oracle.slasher_reward(seized);
+        apply_slash(oracle, colluding_node, seized, slasher_reward, SlashReason::Collusion)?;
+        let mut callback_accounts = ctx.remaining_accounts;
+        if oracle.token_collateral() {
+            // Token collateral sits in the vault; the slasher's token account, the vault, its
+            // authority and the token program lead remaining_accounts
+            let ([slasher_token, vault, vault_authority, token_program], bump) =
+                token_accounts(oracle, ctx.remaining_accounts)?;
+            let oracle_key = oracle.key();
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_program,
+                    token::Transfer {
+                        from: vault,
+                        to: slasher_token,
+                        authority: vault_authority,
+                    },
+                    &[&[b"vault", oracle_key.as_ref(), &[bump]]],
+                ),
+                slasher_reward,
+            )?;
+            callback_accounts = &ctx.remaining_accounts[4..];
+        } else {
+            **oracle.to_account_info().try_borrow_mut_lamports()? -= slasher_reward;
+            **ctx.accounts.slasher.to_account_info().try_borrow_mut_lamports()? += slasher_reward;
+        }
+        colluding_node.slashed_by = Some(slasher);
 
-        colluding_node.slashed = true;
-        
-        emit!(NodeSlashed { 
-            oracle: oracle.key(), 
-            slashed_node: colluding_node.key() 
+        emit!(NodeSlashed {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            slashed_node: colluding_node.key()
         });
 
-        Ok(())
+        notify_slash(
+            &oracle.to_account_info(),
+            &colluding_node.to_account_info(),
+            oracle.config.slash_callback_program,
+            callback_accounts,
+            SlashReason::Collusion,
+        )
     }
 
-    //resolves the request, distributes slashed collateral to consensus nodes
-    pub fn resolve<'info>(
-        ctx: Context<'_, '_, 'info, 'info, Resolve<'info>>
+    //after the reveal window, slash nodes in remaining_accounts that committed but never revealed
+    pub fn slash_no_reveal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SlashNoReveal<'info>>
     ) -> Result<()> {
         let oracle = &mut ctx.accounts.oracle;
         require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
-        require!(Clock::get()?.unix_timestamp > oracle.reveal_end_time, ErrorCode::RevealPhaseNotClosed);
-
-        let mut true_votes = 0;
-        let mut false_votes = 0;
-        let mut total_nodes = 0;
+        require!(
+            Clock::get()?.unix_timestamp > oracle.reveal_end_time,
+            ErrorCode::RevealPhaseNotClosed
+        );
 
         for node_info in ctx.remaining_accounts.iter() {
-            let node = Account::<Node>::try_from(node_info)?;
-            if !node.slashed {
-                total_nodes += 1;
-                if let Some(vote) = node.vote {
-                    if vote {
-                        true_votes += 1;
-                    } else {
-                        false_votes += 1;
-                    }
-                }
+            let mut node = Account::<Node>::try_from(node_info)?;
+            require!(node.oracle == oracle.key(), ErrorCode::NodeOracleMismatch);
+
+            // Nodes that never committed had nothing to withhold
+            if node.state == NodeState::Slashed || node.vote_hash.is_none() || node.vote.is_some() {
+                continue;
             }
+            // No slasher to reward: everything seized goes to the consensus nodes
+            let seized = oracle.slash_amount(node.stake);
+            apply_slash(oracle, &mut node, seized, 0, SlashReason::NoReveal)?;
+            node.exit(&crate::ID)?;
+
+            emit!(NodeSlashed {
+                event_version: EVENT_VERSION,
+                oracle: oracle.key(),
+                slashed_node: node_info.key()
+            });
         }
 
-        oracle.is_resolved = true;
-        oracle.resolution_bit = true_votes > false_votes;
-        let consensus_nodes = if oracle.resolution_bit { true_votes } else { false_votes };
+        Ok(())
+    }
 
-        // Distribute rewards to consensus nodes
-        let reward_per_node = if consensus_nodes > 0 {
-            oracle.collateral * total_nodes / consensus_nodes
-        } else {
-            0
-        };
+    //on adjudicated oracles, the authority supplies the true bit after the reveal window and every
+    //node in remaining_accounts that revealed the other bit is slashed into the consensus pool
+    pub fn slash_wrong_reveal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SlashWrongReveal<'info>>,
+        true_bit: bool,
+    ) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.config.adjudicated, ErrorCode::NotAdjudicated);
+        require!(
+            oracle.config.num_bits == 0 && !oracle.multi_outcome(),
+            ErrorCode::WrongRevealMode
+        );
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            Clock::get()?.unix_timestamp > oracle.reveal_end_time,
+            ErrorCode::RevealPhaseNotClosed
+        );
+        // Nodes may be passed over several calls, but always against the same answer
+        require!(
+            oracle.adjudicated_bit.map_or(true, |bit| bit == true_bit),
+            ErrorCode::AdjudicationMismatch
+        );
+        oracle.adjudicated_bit = Some(true_bit);
 
         for node_info in ctx.remaining_accounts.iter() {
-            let node = Account::<Node>::try_from(node_info)?;
-            if !node.slashed && node.vote == Some(oracle.resolution_bit) {
-                **node_info.try_borrow_mut_lamports()? += reward_per_node;
-                **oracle.to_account_info().try_borrow_mut_lamports()? -= reward_per_node;
+            let mut node = oracle_node(oracle, node_info)?;
+            if node.state == NodeState::Slashed || node.vote != Some(!true_bit as u64) {
+                continue;
             }
-        }
+            let seized = oracle.slash_amount(node.stake);
+            apply_slash(oracle, &mut node, seized, 0, SlashReason::WrongReveal)?;
+            node.slashed_by = Some(ctx.accounts.authority.key());
+            node.exit(&crate::ID)?;
 
-        oracle.phase = Phase::Complete;
+            emit!(NodeSlashed {
+                event_version: EVENT_VERSION,
+                oracle: oracle.key(),
+                slashed_node: node_info.key()
+            });
+        }
 
         Ok(())
     }
-}
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum Phase {
-    Precommit,
-    Commit,
-    Reveal,
-    Complete,
-}
+    //authority reverses a wrongful slash before resolve, paying the witnesses' bonds to the node
+    pub fn overturn_slash(ctx: Context<OverturnSlash>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
 
-#[account]
-pub struct Oracle {
-    pub authority: Pubkey,
-    pub collateral: u64,
-    pub is_resolved: bool,
-    pub resolution_bit: bool,
-    pub phase: Phase,
+        require!(
+            oracle.phase == Phase::Commit || oracle.phase == Phase::Reveal,
+            ErrorCode::InvalidPhase
+        );
+        node.reinstate()?;
+        oracle.slashed_nodes = oracle.slashed_nodes.checked_sub(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        node.slashed_by = None;
+
+        // Release the collateral from the slashed pool and forfeit every witness's bond to the
+        // vindicated node; bonds first make up any slasher reward already paid out of the collateral,
+        // and whatever they can't cover comes out of the reinstated stake
+        let seized = node.slashed_amount;
+        let slasher_reward = node.slash_reward;
+        let bonds = oracle
+            .config
+            .slash_bond
+            .checked_mul(node.slash_witnesses.len() as u64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let forfeited = bonds.saturating_sub(slasher_reward);
+        let shortfall = slasher_reward.saturating_sub(bonds);
+        node.stake = node.stake.checked_sub(shortfall).ok_or(ErrorCode::ArithmeticOverflow)?;
+        let node_key = node.key();
+        if let Some(record) = oracle.votes_snapshot.iter_mut().find(|record| record.node == node_key) {
+            record.stake = record.stake.saturating_sub(shortfall);
+        }
+        node.slash_witnesses.clear();
+        node.slashed_amount = 0;
+        node.slash_reward = 0;
+        node.slash_reason = None;
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= forfeited;
+        **node.to_account_info().try_borrow_mut_lamports()? += forfeited;
+        oracle.slashed_pool = oracle
+            .slashed_pool
+            .checked_sub(seized.checked_sub(slasher_reward).ok_or(ErrorCode::ArithmeticOverflow)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        oracle.slashed_this_round = oracle.slashed_this_round.checked_sub(seized).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    //resolves the request, distributes slashed collateral to consensus nodes
+    pub fn resolve<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Resolve<'info>>
+    ) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let keeper = ctx.accounts.keeper.to_account_info();
+
+        // Authority-gated oracles only take resolve from the authority, before the resolve deadline
+        if oracle.config.authority_resolves {
+            require!(keeper.key() == oracle.authority, ErrorCode::UnauthorizedAccess);
+            if oracle.config.resolve_deadline > 0 {
+                require!(
+                    Clock::get()?.unix_timestamp <= resolve_deadline(oracle)?,
+                    ErrorCode::ResolveDeadlinePassed
+                );
+            }
+
+            // Resolved on time, so the authority's liveness bond goes back
+            let resolution_bond = oracle.config.resolution_bond;
+            **oracle.to_account_info().try_borrow_mut_lamports()? -= resolution_bond;
+            **keeper.try_borrow_mut_lamports()? += resolution_bond;
+        }
+
+        // More than half the committee must have revealed unless the oracle opts out. A round nobody
+        // revealed in is cancelled or falls back to no_reveal_default instead, and
+        // penalize_late_resolution skips this: once the resolve deadline passes an outcome is forced
+        if !oracle.config.allow_minority_resolve && oracle.revealed_nodes > 0 {
+            require!(
+                oracle.revealed_nodes > oracle.total_nodes / 2,
+                ErrorCode::InsufficientParticipation
+            );
+        }
+
+        let fee_recipient = ctx.accounts.fee_recipient.to_account_info();
+        settle_resolution(oracle, &keeper, &fee_recipient, ctx.remaining_accounts)
+    }
+
+    //tally up to `count` revealed votes after the reveal phase closes, so committees too large to
+    //tally within one transaction's compute budget can be resolved in chunks
+    pub fn tally_votes(ctx: Context<TallyVotes>, count: u64) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            Clock::get()?.unix_timestamp > oracle.reveal_end_time,
+            ErrorCode::RevealPhaseNotClosed
+        );
+        require!(
+            (oracle.tallied_nodes as usize) < oracle.votes_snapshot.len(),
+            ErrorCode::AllVotesTallied
+        );
+
+        tally_records(oracle, usize::try_from(count).unwrap_or(usize::MAX))
+    }
+
+    //resolve from the totals tally_votes accumulated; every revealed vote must have been tallied
+    pub fn finalize_resolution<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Resolve<'info>>
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.oracle.tallied_nodes as usize == ctx.accounts.oracle.votes_snapshot.len(),
+            ErrorCode::TallyIncomplete
+        );
+        resolve(ctx)
+    }
+
+    //once the resolve deadline passes, a node penalizes the authority's bond and resolves itself
+    pub fn penalize_late_resolution<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PenalizeLateResolution<'info>>
+    ) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let caller = ctx.accounts.caller.to_account_info();
+
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            oracle.config.authority_resolves && oracle.config.resolve_deadline > 0,
+            ErrorCode::NoResolveDeadline
+        );
+        require!(
+            Clock::get()?.unix_timestamp > resolve_deadline(oracle)?,
+            ErrorCode::ResolveDeadlineNotReached
+        );
+
+        // The penalty is split evenly across the passed nodes that revealed; the caller must run one
+        let mut participants: Vec<&AccountInfo<'info>> = Vec::new();
+        let mut caller_participates = false;
+        for node_info in ctx.remaining_accounts.iter() {
+            let node = Account::<Node>::try_from(node_info)?;
+            require!(node.oracle == oracle.key(), ErrorCode::NodeOracleMismatch);
+            let revealed = oracle.votes_snapshot.iter().any(|record| record.node == node_info.key());
+            let counted = participants.iter().any(|other| other.key == node_info.key);
+            if node.state != NodeState::Slashed && revealed && !counted {
+                participants.push(node_info);
+                caller_participates |= node.authority == caller.key();
+            }
+        }
+        require!(caller_participates, ErrorCode::UnauthorizedAccess);
+
+        let resolution_bond = oracle.config.resolution_bond;
+        let penalty = resolution_bond
+            .checked_mul(oracle.config.late_penalty_bps as u64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / 10_000;
+        let share = penalty / participants.len() as u64;
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= resolution_bond;
+        for node_info in participants.iter() {
+            **node_info.try_borrow_mut_lamports()? += share;
+        }
+        let returned = resolution_bond - share * participants.len() as u64;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += returned;
+
+        let fee_recipient = ctx.accounts.fee_recipient.to_account_info();
+        settle_resolution(oracle, &caller, &fee_recipient, ctx.remaining_accounts)
+    }
+
+    //move a consensus node's payout from a completed oracle into a new oracle as its stake, whether
+    //the oracle still holds it (pull_rewards, or left out of resolve) or resolve credited it to the
+    //node account. The move is a join of the destination and goes through the same checks
+    pub fn migrate_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateCollateral<'info>>
+    ) -> Result<()> {
+        let source_oracle = &mut ctx.accounts.source_oracle;
+        let source_node = &mut ctx.accounts.source_node;
+        let destination_oracle = &mut ctx.accounts.destination_oracle;
+        let destination_node = &mut ctx.accounts.destination_node;
+        let authority = &ctx.accounts.authority;
+
+        require!(
+            !source_oracle.token_collateral() && !destination_oracle.token_collateral(),
+            ErrorCode::NativeCollateralOnly
+        );
+        require!(source_oracle.phase == Phase::Complete, ErrorCode::InvalidPhase);
+        require!(source_node.oracle == source_oracle.key(), ErrorCode::NodeOracleMismatch);
+        require!(source_node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
+        require!(source_node.state != NodeState::Withdrawn, ErrorCode::AlreadyClaimed);
+        // Once the node reclaimed, neither the oracle nor the node account holds its payout
+        require!(!source_node.claimed, ErrorCode::AlreadyClaimed);
+        require!(
+            source_node.vote.map_or(false, |mask| source_oracle.is_consensus(mask)),
+            ErrorCode::NotConsensusNode
+        );
+        check_join(
+            destination_oracle,
+            &mut ctx.accounts.membership,
+            authority.key(),
+            ctx.remaining_accounts.first(),
+        )?;
+
+        // A payout resolve already credited sits on the node account above its rent reserve
+        let (payout, payer) = if source_node.rewarded {
+            let node_info = source_node.to_account_info();
+            let rent_floor = Rent::get()?.minimum_balance(node_info.data_len());
+            (node_info.lamports().saturating_sub(rent_floor), node_info)
+        } else {
+            let payout = consensus_payout(
+                source_oracle,
+                &source_node.key(),
+                late_reveal_adjustments(source_oracle)?,
+            )?;
+            (payout, source_oracle.to_account_info())
+        };
+
+        // Move the destination's required collateral across, returning the rest of the node's
+        // payout to its authority. With deferred funding the collateral arrives through fund_join
+        // instead, so the whole payout goes back
+        let stake = if destination_oracle.config.deferred_funding {
+            0
+        } else {
+            destination_oracle.config.collateral
+        };
+        require!(payout >= stake, ErrorCode::InsufficientCollateral);
+        **payer.try_borrow_mut_lamports()? -= payout;
+        **destination_oracle.to_account_info().try_borrow_mut_lamports()? += stake;
+        **authority.to_account_info().try_borrow_mut_lamports()? += payout - stake;
+
+        source_node.rewarded = true;
+        source_node.claimed = true;
+        source_node.transition(NodeState::Withdrawn)?;
+
+        reset_node(destination_node, authority.key(), destination_oracle.key());
+        if destination_oracle.config.deferred_funding {
+            destination_node.state = NodeState::PendingJoin;
+            destination_node.stake = 0;
+            return Ok(());
+        }
+        destination_node.state = NodeState::Joined;
+        destination_node.stake = stake;
+
+        destination_oracle.total_nodes = destination_oracle.total_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(NodeJoined {
+            event_version: EVENT_VERSION,
+            oracle: destination_oracle.key(),
+            node: destination_node.key(),
+            authority: authority.key(),
+            stake,
+        });
+
+        Ok(())
+    }
+
+    //last-resort escape hatch: long after the reveal deadline of an oracle nobody resolved, the
+    //authority sweeps everything above the rent reserve to itself and the oracle is marked Complete
+    pub fn authority_drain(ctx: Context<AuthorityDrain>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(!oracle.token_collateral(), ErrorCode::NativeCollateralOnly);
+        require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(oracle.reveal_end_time != 0, ErrorCode::RevealNotStarted);
+        let drainable_at = oracle
+            .reveal_end_time
+            .checked_add(EMERGENCY_TIMEOUT)
+            .ok_or(ErrorCode::InvalidDeadline)?;
+        require!(
+            Clock::get()?.unix_timestamp >= drainable_at,
+            ErrorCode::EmergencyTimeoutNotReached
+        );
+
+        let oracle_info = oracle.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(oracle_info.data_len());
+        let amount = oracle_info.lamports().saturating_sub(rent_floor);
+        **oracle_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+        oracle.phase = Phase::Complete;
+
+        emit!(OracleDrained {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            authority: oracle.authority,
+            amount,
+        });
+        Ok(())
+    }
+
+    //refund nodes of a cancelled, tied or inconclusive oracle, cancelling it first if the request was
+    //never started before the abandon timeout
+    pub fn claim_abandoned<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAbandoned<'info>>
+    ) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+
+        if oracle.phase == Phase::Precommit {
+            require!(oracle.config.abandon_timeout > 0, ErrorCode::AbandonTimeoutNotReached);
+            let abandoned_at = oracle
+                .created_at
+                .checked_add(oracle.config.abandon_timeout)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            require!(
+                Clock::get()?.unix_timestamp >= abandoned_at,
+                ErrorCode::AbandonTimeoutNotReached
+            );
+            oracle.phase = Phase::Cancelled;
+        }
+        require!(
+            matches!(oracle.phase, Phase::Cancelled | Phase::Tied | Phase::Inconclusive),
+            ErrorCode::InvalidPhase
+        );
+
+        // remaining_accounts are (node, node_authority) pairs; refunds can be batched
+        // across several calls once the oracle is cancelled. Token collateral oracles lead with
+        // (vault, vault authority, token program) and pair each node with its authority's token account
+        let (vault_accounts, pairs) = if oracle.token_collateral() {
+            require!(ctx.remaining_accounts.len() >= 3, ErrorCode::InvalidTokenAccounts);
+            ctx.remaining_accounts.split_at(3)
+        } else {
+            ctx.remaining_accounts.split_at(0)
+        };
+        require!(pairs.len() % 2 == 0, ErrorCode::InvalidRemainingAccounts);
+        for pair in pairs.chunks(2) {
+            let mut node = Account::<Node>::try_from(&pair[0])?;
+            let recipient = &pair[1];
+            require!(node.oracle == oracle.key(), ErrorCode::NodeOracleMismatch);
+            if oracle.token_collateral() {
+                let owner_token = Account::<TokenAccount>::try_from(recipient)?;
+                require!(
+                    owner_token.owner == node.authority && owner_token.mint == oracle.mint,
+                    ErrorCode::InvalidTokenAccounts
+                );
+            } else {
+                require!(node.authority == recipient.key(), ErrorCode::UnauthorizedAccess);
+            }
+
+            // Nodes with nothing left to refund are skipped so one of them can't fail the batch;
+            // revealers also collect their share of the slashed pool from a tie
+            let refund = match node.state {
+                NodeState::Withdrawn | NodeState::PendingJoin => continue,
+                NodeState::Slashed if node.claimed => continue,
+                NodeState::Slashed => node.stake.saturating_sub(node.slashed_amount),
+                _ if node.vote.is_some() => {
+                    node.stake.checked_add(oracle.refund_share).ok_or(ErrorCode::ArithmeticOverflow)?
+                }
+                _ => node.stake,
+            };
+            let accounts = [std::slice::from_ref(recipient), vault_accounts].concat();
+            pay_collateral(oracle, recipient, &accounts, refund)?;
+
+            if node.state == NodeState::Slashed {
+                node.claimed = true;
+            } else {
+                node.transition(NodeState::Withdrawn)?;
+            }
+            node.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    //pay a consensus node that was left out of resolve's remaining_accounts
+    pub fn claim_missed_reward(ctx: Context<ClaimMissedReward>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+        require!(!oracle.token_collateral(), ErrorCode::NativeCollateralOnly);
+
+        require!(oracle.phase == Phase::Complete, ErrorCode::InvalidPhase);
+        require!(node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
+        require!(
+            node.vote.map_or(false, |mask| oracle.is_consensus(mask)),
+            ErrorCode::NotConsensusNode
+        );
+        require!(!node.rewarded, ErrorCode::AlreadyClaimed);
+
+        // Paid from whatever is left in the pool, never from the rent reserve
+        let reward = consensus_payout(oracle, &node.key(), late_reveal_adjustments(oracle)?)?;
+        let oracle_info = oracle.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(oracle_info.data_len());
+        require!(
+            oracle_info.lamports() >= rent_floor + reward,
+            ErrorCode::WouldBreakRentExemption
+        );
+
+        **oracle_info.try_borrow_mut_lamports()? -= reward;
+        **node.to_account_info().try_borrow_mut_lamports()? += reward;
+        node.rewarded = true;
+
+        Ok(())
+    }
+
+    //withdraw a revealed node's collateral to its authority once the oracle completes, with its
+    //reward if it voted with consensus
+    pub fn reclaim_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReclaimCollateral<'info>>
+    ) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+
+        // A partially slashed node gets back whatever the slash left it, however the oracle settled
+        if node.state == NodeState::Slashed {
+            require!(
+                matches!(
+                    oracle.phase,
+                    Phase::Complete | Phase::Cancelled | Phase::Tied | Phase::Inconclusive
+                ),
+                ErrorCode::InvalidPhase
+            );
+            require!(!node.claimed, ErrorCode::AlreadyClaimed);
+            let remainder = node.stake.saturating_sub(node.slashed_amount);
+            require!(remainder > 0, ErrorCode::AlreadySlashed);
+            let authority = ctx.accounts.authority.to_account_info();
+            pay_collateral(oracle, &authority, ctx.remaining_accounts, remainder)?;
+            node.claimed = true;
+            return Ok(());
+        }
+
+        require!(oracle.phase == Phase::Complete, ErrorCode::InvalidPhase);
+        // Honest revealers on the losing side get their deposit back too; only slashing costs collateral
+        require!(node.vote.is_some(), ErrorCode::NotConsensusNode);
+        require!(!node.claimed, ErrorCode::AlreadyClaimed);
+        let consensus = node.vote.map_or(false, |mask| oracle.is_consensus(mask));
+        // Stake topped up past the joining collateral comes back with it
+        let deposit = node.stake;
+        // A losing revealer resolve never paid (token collateral, a dispute window, or left out of
+        // resolve) still has its loser rebate to collect
+        let unpaid_loss = deposit.checked_add(oracle.loser_rebate).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        // Token collateral is only ever paid out here, straight from the vault
+        if oracle.token_collateral() {
+            let amount = if consensus {
+                consensus_payout(oracle, &node.key(), late_reveal_adjustments(oracle)?)?
+            } else {
+                unpaid_loss
+            };
+            let authority = ctx.accounts.authority.to_account_info();
+            pay_collateral(oracle, &authority, ctx.remaining_accounts, amount)?;
+            node.rewarded = true;
+            node.claimed = true;
+            return Ok(());
+        }
+
+        // A node resolve skipped is paid its share straight from the pool; a losing node's
+        // deposit stays in the oracle unless auto_settle already refunded it
+        let authority = ctx.accounts.authority.to_account_info();
+        let owed = match (consensus, node.rewarded) {
+            (true, false) => Some(consensus_payout(oracle, &node.key(), late_reveal_adjustments(oracle)?)?),
+            (true, true) => None,
+            (false, true) if oracle.config.auto_settle => None,
+            (false, true) => Some(deposit),
+            (false, false) => Some(unpaid_loss),
+        };
+        if let Some(reward) = owed {
+            let oracle_info = oracle.to_account_info();
+            let rent_floor = Rent::get()?.minimum_balance(oracle_info.data_len());
+            require!(
+                oracle_info.lamports() >= rent_floor + reward,
+                ErrorCode::WouldBreakRentExemption
+            );
+            **oracle_info.try_borrow_mut_lamports()? -= reward;
+            **authority.try_borrow_mut_lamports()? += reward;
+            node.rewarded = true;
+        }
+
+        // Whatever resolve already credited to the node account goes on to the authority
+        let node_info = node.to_account_info();
+        let credited = node_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(node_info.data_len()));
+        **node_info.try_borrow_mut_lamports()? -= credited;
+        **authority.try_borrow_mut_lamports()? += credited;
+        node.claimed = true;
+
+        Ok(())
+    }
+
+    //return a settled node account's rent to its authority. A revealed node must reclaim its
+    //collateral (and any reward) first; after a cancelled or tied request it must have claimed its stake
+    pub fn close_node<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseNode<'info>>
+    ) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        let node = &ctx.accounts.node;
+
+        // An abstainer left out of resolve still has its stake on the oracle; the node's
+        // lamports go to the authority when it closes, token stakes straight to its token account
+        if node.state == NodeState::Abstained && !node.rewarded {
+            require!(
+                matches!(
+                    oracle.phase,
+                    Phase::Complete | Phase::Cancelled | Phase::Tied | Phase::Inconclusive
+                ),
+                ErrorCode::InvalidPhase
+            );
+            pay_collateral(oracle, &node.to_account_info(), ctx.remaining_accounts, node.stake)?;
+            return Ok(());
+        }
+
+        // A partially slashed node must first reclaim its remainder
+        let forfeited = matches!(node.state, NodeState::Withdrawn | NodeState::Abstained)
+            || (node.state == NodeState::Slashed
+                && (node.claimed || node.slashed_amount >= node.stake));
+        // Revealers are owed collateral either way unless auto_settle already refunded a loser
+        let refunded = oracle.config.auto_settle
+            && node.rewarded
+            && !node.vote.map_or(false, |mask| oracle.is_consensus(mask));
+        let settled = match oracle.phase {
+            Phase::Complete => {
+                forfeited
+                    || (node.state != NodeState::Slashed
+                        && (node.claimed || node.vote.is_none() || refunded))
+            }
+            Phase::Cancelled | Phase::Tied | Phase::Inconclusive => forfeited,
+            _ => return err!(ErrorCode::InvalidPhase),
+        };
+        require!(settled, ErrorCode::UnclaimedFunds);
+        Ok(())
+    }
+
+    //pull a consensus node's reward; with pull_rewards resolve only fixes reward_per_node and each
+    //node collects here, so a mis-passed account can't fail resolution for everyone
+    pub fn claim_reward<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReclaimCollateral<'info>>
+    ) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        require!(
+            ctx.accounts.node.vote.map_or(false, |mask| oracle.is_consensus(mask)),
+            ErrorCode::NotConsensusNode
+        );
+        reclaim_collateral(ctx)
+    }
+
+    //credit a consensus node's authority with one reputation point for a completed oracle
+    pub fn record_reputation(ctx: Context<RecordReputation>) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+        let reputation = &mut ctx.accounts.reputation;
+
+        require!(oracle.phase == Phase::Complete, ErrorCode::InvalidPhase);
+        require!(node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
+        require!(
+            node.vote.map_or(false, |mask| oracle.is_consensus(mask)),
+            ErrorCode::NotConsensusNode
+        );
+        require!(!node.reputation_recorded, ErrorCode::AlreadyClaimed);
+
+        reputation.authority = node.authority;
+        reputation.score = reputation.score.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        node.reputation_recorded = true;
+
+        Ok(())
+    }
+
+    //fold a finished node's outcome into its authority's operator record, which spans every oracle
+    pub fn record_operator(ctx: Context<RecordOperator>) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        let node = &mut ctx.accounts.node;
+        let operator = &mut ctx.accounts.operator;
+
+        require!(
+            matches!(
+                oracle.phase,
+                Phase::Complete | Phase::Cancelled | Phase::Tied | Phase::Inconclusive
+            ),
+            ErrorCode::InvalidPhase
+        );
+        require!(!node.operator_recorded, ErrorCode::AlreadyClaimed);
+
+        operator.authority = node.authority;
+        if node.state == NodeState::Slashed {
+            operator.slashed_count = operator.slashed_count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        } else if let Some(mask) = node.vote {
+            operator.total_participations = operator
+                .total_participations
+                .checked_add(1)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            if oracle.phase == Phase::Complete && oracle.is_consensus(mask) {
+                operator.correct_resolutions = operator
+                    .correct_resolutions
+                    .checked_add(1)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+        }
+        node.operator_recorded = true;
+
+        Ok(())
+    }
+
+    //estimate what resolve pays a node if `vote` wins and the node reveals it now, pricing only
+    //already-revealed votes the way settle_resolution does. Binary oracles only; 0 wherever resolve
+    //would pay no reward: a round that ended without winners, an unbroken tie or a missed quorum
+    pub fn estimate_reward(ctx: Context<EstimateReward>, vote: bool) -> Result<u64> {
+        let oracle = &ctx.accounts.oracle;
+        let node = &ctx.accounts.node;
+        require!(node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
+        require!(
+            oracle.config.num_bits == 0 && !oracle.multi_outcome(),
+            ErrorCode::WrongRevealMode
+        );
+        if matches!(oracle.phase, Phase::Cancelled | Phase::Tied | Phase::Inconclusive)
+            || oracle.adjudicated_bit.map_or(false, |bit| bit != vote)
+        {
+            return Ok(0);
+        }
+
+        // Settle a copy of the oracle as if `vote` had won, with the estimating node voting it
+        let mut preview = (*ctx.accounts.oracle).clone();
+        preview.resolution_bit = vote;
+        preview.resolution_mask = vote as u64;
+        preview.resolution_outcome = vote as u8;
+        match preview.votes_snapshot.iter_mut().find(|record| record.node == node.key()) {
+            Some(record) => {
+                record.vote = vote;
+                record.mask = vote as u64;
+            }
+            None => preview.votes_snapshot.push(VoteRecord {
+                node: node.key(),
+                authority: node.authority,
+                vote,
+                mask: vote as u64,
+                revealed_at: Clock::get()?.unix_timestamp,
+                stake: node.stake,
+            }),
+        }
+        preview.final_true_votes = preview.votes_snapshot.iter().filter(|record| record.vote).count() as u64;
+        preview.final_false_votes = preview.votes_snapshot.len() as u64 - preview.final_true_votes;
+
+        // Resolve's tie and quorum checks, on the same weights it tallies
+        if preview.adjudicated_bit.is_none() {
+            let weight = |record: &VoteRecord| if preview.config.stake_weighted { record.stake as u128 } else { 1 };
+            let (true_weight, false_weight) = preview.votes_snapshot.iter().fold((0, 0), |(t, f), record| {
+                if record.vote {
+                    (t + weight(record), f)
+                } else {
+                    (t, f + weight(record))
+                }
+            });
+            if true_weight == false_weight && preview.config.tie_break_policy == TieBreakPolicy::Unresolved {
+                return Ok(0);
+            }
+            let quorum_bps = preview.config.quorum_bps as u128;
+            if quorum_bps > 0 && true_weight.max(false_weight) * 10_000 < quorum_bps * (true_weight + false_weight) {
+                return Ok(0);
+            }
+        }
+        preview.reward_pool = preview.slashed_pool - protocol_fee(&preview);
+        price_rewards(&mut preview)?;
+
+        let adjustments = late_reveal_adjustments(&preview)?;
+        consensus_payout(&preview, &node.key(), adjustments)
+    }
+
+    //summarize the nodes passed in remaining_accounts in one call
+    pub fn roster<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Roster<'info>>
+    ) -> Result<Vec<NodeSummary>> {
+        let oracle = &ctx.accounts.oracle;
+        require!(
+            ctx.remaining_accounts.len() <= MAX_ROSTER_SIZE,
+            ErrorCode::RosterTooLarge
+        );
+
+        let mut roster = Vec::with_capacity(ctx.remaining_accounts.len());
+        for node_info in ctx.remaining_accounts.iter() {
+            let node = Account::<Node>::try_from(node_info)?;
+            require!(node.oracle == oracle.key(), ErrorCode::NodeOracleMismatch);
+            roster.push(NodeSummary {
+                authority: node.authority,
+                state: node.state,
+                stake: node.stake,
+            });
+        }
+
+        Ok(roster)
+    }
+
+    //return the oracle's fixed configuration, separate from its per-round state
+    pub fn config(ctx: Context<ReadConfig>) -> Result<OracleConfig> {
+        Ok(ctx.accounts.oracle.config.clone())
+    }
+
+    //return the oracle's progress through the current round without decoding the raw account
+    pub fn oracle_status(ctx: Context<ReadConfig>) -> Result<OracleStatus> {
+        let oracle = &ctx.accounts.oracle;
+        Ok(OracleStatus {
+            phase: oracle.phase,
+            total_nodes: oracle.total_nodes,
+            committed_nodes: oracle.committed_nodes,
+            revealed_nodes: oracle.revealed_nodes,
+            reveal_end_time: oracle.reveal_end_time,
+            is_resolved: oracle.is_resolved,
+        })
+    }
+
+    //return one node's revealed vote and standing, so light clients can check an operator without
+    //decoding the account. Binary oracles only, since the vote is read back as a single bit
+    pub fn get_node_vote(ctx: Context<ReadNode>) -> Result<NodeVote> {
+        let oracle = &ctx.accounts.oracle;
+        let node = &ctx.accounts.node;
+        require!(
+            oracle.config.num_bits == 0 && !oracle.multi_outcome(),
+            ErrorCode::WrongRevealMode
+        );
+        Ok(NodeVote {
+            vote: node.vote.map(|bit| bit == 1),
+            slashed: node.state == NodeState::Slashed,
+            claimed: node.claimed,
+        })
+    }
+
+    //serialize the oracle into an export account ahead of a program upgrade, moving its funds along
+    pub fn export_state(ctx: Context<ExportState>) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        // Token collateral sits in a vault owned by a PDA of this address, which the import can't move
+        require!(!oracle.token_collateral(), ErrorCode::NativeCollateralOnly);
+        let oracle_info = oracle.to_account_info();
+        let rent = Rent::get()?.minimum_balance(oracle_info.data_len());
+
+        let export = &mut ctx.accounts.export;
+        export.version = EXPORT_VERSION;
+        export.oracle = oracle.key();
+        export.authority = oracle.authority;
+        export.space = oracle_info.data_len() as u64;
+        export.funds = oracle_info.lamports().checked_sub(rent).ok_or(ErrorCode::ArithmeticOverflow)?;
+        export.state = oracle
+            .try_to_vec()
+            .map_err(|_| ErrorCode::InvalidExportState)?;
+
+        // Closing the oracle sends every lamport to the export; node accounts keep pointing at its address
+        Ok(())
+    }
+
+    //recreate an exported oracle at the [b"oracle", exported address] PDA, converting older layouts and
+    //restoring its phase, state and funds; its nodes follow with import_node
+    pub fn import_state(ctx: Context<ImportState>) -> Result<()> {
+        let export = &ctx.accounts.export;
+        let mut state = export.decode_state()?;
+        state.migrated_from = Some(export.oracle);
+
+        let funds = export.funds;
+        **export.to_account_info().try_borrow_mut_lamports()? -= funds;
+        **ctx.accounts.oracle.to_account_info().try_borrow_mut_lamports()? += funds;
+        ctx.accounts.oracle.set_inner(state);
+        Ok(())
+    }
+
+    //move a node of the exported oracle over to its imported successor, keeping its round state
+    pub fn import_node(ctx: Context<ImportNode>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let source = ctx.accounts.source_node.key();
+        let destination = ctx.accounts.node.key();
+
+        // Reveals and the transition bonus were recorded against the node's old address
+        for record in oracle.votes_snapshot.iter_mut().filter(|record| record.node == source) {
+            record.node = destination;
+        }
+        if oracle.transition_node == Some(source) {
+            oracle.transition_node = Some(destination);
+        }
+
+        let mut node = (*ctx.accounts.source_node).clone();
+        node.oracle = oracle.key();
+        ctx.accounts.node.set_inner(node);
+        Ok(())
+    }
+
+    //record a completed resolution in a program-owned account external verifiers can read
+    pub fn produce_attestation(ctx: Context<ProduceAttestation>) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Complete, ErrorCode::InvalidPhase);
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.oracle = oracle.key();
+        attestation.round = oracle.round;
+        attestation.resolution_bit = oracle.resolution_bit;
+        attestation.reveal_end_time = oracle.reveal_end_time;
+        attestation.binding = attestation_binding(
+            &oracle.key(),
+            oracle.round,
+            oracle.resolution_bit,
+            oracle.reveal_end_time,
+        );
+        attestation.attested_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    //contest a resolution during the dispute window by posting the dispute bond
+    pub fn raise_dispute(ctx: Context<RaiseDispute>) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        require!(oracle.phase == Phase::Disputable, ErrorCode::InvalidPhase);
+        require!(oracle.dispute.is_none(), ErrorCode::DisputePending);
+        require!(
+            Clock::get()?.unix_timestamp < oracle.dispute_end_time,
+            ErrorCode::DisputeWindowClosed
+        );
+
+        let dispute_bond = oracle.config.dispute_bond;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.disputer.to_account_info(),
+                    to: ctx.accounts.oracle.to_account_info(),
+                },
+            ),
+            dispute_bond,
+        )?;
+
+        ctx.accounts.oracle.dispute = Some(ctx.accounts.disputer.key());
+        Ok(())
+    }
+
+    //mark the resolution final once the dispute window has passed without a pending dispute.
+    //Oracles without a dispute window resolve straight to Complete and are only stamped here
+    pub fn finalize(ctx: Context<Finalize>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            matches!(oracle.phase, Phase::Disputable | Phase::Complete),
+            ErrorCode::InvalidPhase
+        );
+        require!(oracle.finalized_at.is_none(), ErrorCode::AlreadyFinalized);
+        require!(oracle.dispute.is_none(), ErrorCode::DisputePending);
+        require!(now >= oracle.dispute_end_time, ErrorCode::DisputeWindowOpen);
+
+        oracle.phase = Phase::Complete;
+        oracle.finalized_at = Some(now);
+
+        emit!(ResolutionFinalized {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            resolution_bit: oracle.resolution_bit,
+            finalized_at: now,
+        });
+
+        Ok(())
+    }
+
+    //escalated dispute: the authority rules on the outcome, given like a reveal as the bit of a
+    //binary oracle, an outcome index or one bit per question. Upholding the dispute replaces the
+    //resolution, re-prices the held revealer payouts for the new consensus side and returns the
+    //bond; rejecting it forfeits the bond to the authority
+    pub fn settle_dispute(ctx: Context<SettleDispute>, resolution: u64) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(oracle.phase == Phase::Disputable, ErrorCode::InvalidPhase);
+        require!(oracle.dispute == Some(ctx.accounts.disputer.key()), ErrorCode::NotDisputer);
+        if oracle.multi_outcome() {
+            require!(
+                resolution < oracle.config.outcome_count as u64,
+                ErrorCode::InvalidDisputeResolution
+            );
+        } else {
+            require!(resolution & !oracle.bits_mask() == 0, ErrorCode::InvalidDisputeResolution);
+        }
+
+        let dispute_bond = oracle.config.dispute_bond;
+        let recipient = if resolution != oracle.resolution_mask {
+            oracle.resolution_mask = resolution;
+            if oracle.multi_outcome() {
+                oracle.resolution_outcome = resolution as u8;
+                oracle.resolution_bit = oracle.resolution_outcome == 1;
+            } else {
+                oracle.resolution_bit = resolution & 1 == 1;
+                oracle.resolution_outcome = oracle.resolution_bit as u8;
+            }
+            price_rewards(oracle)?;
+            ctx.accounts.disputer.to_account_info()
+        } else {
+            ctx.accounts.authority.to_account_info()
+        };
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= dispute_bond;
+        **recipient.try_borrow_mut_lamports()? += dispute_bond;
+
+        oracle.phase = Phase::Complete;
+        oracle.finalized_at = Some(now);
+
+        emit!(ResolutionFinalized {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            resolution_bit: oracle.resolution_bit,
+            finalized_at: now,
+        });
+
+        Ok(())
+    }
+
+    //first step of an authority handover: the current authority names its successor
+    pub fn transfer_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(
+            ctx.accounts.authority.key() == oracle.authority,
+            ErrorCode::UnauthorizedAccess
+        );
+
+        oracle.pending_authority = Some(new_authority);
+        Ok(())
+    }
+
+    //second step: the proposed authority signs to take over, so a mistyped key never gains control
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(
+            oracle.pending_authority == Some(ctx.accounts.new_authority.key()),
+            ErrorCode::UnauthorizedAccess
+        );
+
+        let previous_authority = oracle.authority;
+        oracle.authority = ctx.accounts.new_authority.key();
+        oracle.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            previous_authority,
+            new_authority: oracle.authority,
+        });
+        Ok(())
+    }
+
+    //open another question for the oracle's current node set. Each request runs its own
+    //commit-reveal round in a Request account; collateral and slashing stay with the oracle
+    pub fn create_request(ctx: Context<CreateRequest>, question_hash: [u8; 32]) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        require!(!oracle.paused, ErrorCode::Paused);
+        require!(question_hash != [0u8; 32], ErrorCode::EmptyQuestionHash);
+        require!(oracle.total_nodes > 0, ErrorCode::NotEnoughNodes);
+
+        let request = &mut ctx.accounts.request;
+        request.oracle = oracle.key();
+        request.index = oracle.request_count;
+        request.question_hash = question_hash;
+        request.phase = Phase::Commit;
+        request.committed_nodes = 0;
+        request.reveal_end_time = 0;
+        request.true_votes = 0;
+        request.false_votes = 0;
+        request.resolution_bit = false;
+        request.slashed_nodes = 0;
+        request.slashed_pool = 0;
+        request.reward_per_node = 0;
+        oracle.request_count = oracle.request_count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(RequestCreated {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            request: request.key(),
+            index: request.index,
+            question_hash,
+        });
+        Ok(())
+    }
+
+    //commit a vote on one request; the Ballot account is created here, so a node commits once per request
+    pub fn commit_request(ctx: Context<CommitRequest>, vote_hash: [u8; 32]) -> Result<()> {
+        let request = &mut ctx.accounts.request;
+        require!(!ctx.accounts.oracle.paused, ErrorCode::Paused);
+        require!(request.phase == Phase::Commit, ErrorCode::InvalidPhase);
+        require!(
+            matches!(
+                ctx.accounts.node.state,
+                NodeState::Joined | NodeState::Committed | NodeState::Revealed
+            ),
+            ErrorCode::InvalidNodeState
+        );
+
+        let ballot = &mut ctx.accounts.ballot;
+        ballot.request = request.key();
+        ballot.node = ctx.accounts.node.key();
+        ballot.vote_hash = vote_hash;
+        ballot.vote = None;
+        ballot.rewarded = false;
+        request.committed_nodes = request.committed_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        // The reveal window opens once every node the oracle still counts has committed
+        if request_fully_committed(&ctx.accounts.oracle, request) {
+            start_request_reveal(&ctx.accounts.oracle, request)?;
+        }
+        Ok(())
+    }
+
+    //open a request's reveal window once the nodes still waited on have left or been slashed
+    pub fn close_request_commit(ctx: Context<CloseRequestCommit>) -> Result<()> {
+        let request = &mut ctx.accounts.request;
+        require!(request.phase == Phase::Commit, ErrorCode::InvalidPhase);
+        require!(
+            request_fully_committed(&ctx.accounts.oracle, request),
+            ErrorCode::CommitPhaseNotClosed
+        );
+        start_request_reveal(&ctx.accounts.oracle, request)
+    }
+
+    //slash a node whose request vote was leaked before the reveal window, as slash_colluding does
+    //for the oracle's own round; the seized collateral goes to the request's consensus ballots
+    pub fn slash_request_colluding(ctx: Context<SlashRequestColluding>, vote: bool, nonce: [u8; 32]) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let request = &mut ctx.accounts.request;
+        let node = &mut ctx.accounts.node;
+        require!(!oracle.paused, ErrorCode::Paused);
+        require!(!oracle.token_collateral(), ErrorCode::NativeCollateralOnly);
+        require!(request.phase == Phase::Commit, ErrorCode::InvalidPhase);
+        require!(node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
+        require!(!node.claimed, ErrorCode::AlreadyClaimed);
+        require!(
+            compute_vote_hash(&request.key(), &node.key(), vote, &nonce, None) == ctx.accounts.ballot.vote_hash,
+            ErrorCode::InvalidCollusion
+        );
+
+        let seized = oracle.slash_amount(node.stake);
+        let slasher_reward = oracle.slasher_reward(seized);
+        slash_on_request(oracle, request, node, seized, slasher_reward, SlashReason::Collusion)?;
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= slasher_reward;
+        **ctx.accounts.slasher.to_account_info().try_borrow_mut_lamports()? += slasher_reward;
+        node.slashed_by = Some(ctx.accounts.slasher.key());
+
+        emit!(NodeSlashed {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            slashed_node: node.key()
+        });
+        Ok(())
+    }
+
+    //after a request's reveal window, slash a node that committed to it but never revealed
+    pub fn slash_request_no_reveal(ctx: Context<SlashRequestNoReveal>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let request = &mut ctx.accounts.request;
+        let node = &mut ctx.accounts.node;
+        require!(!oracle.token_collateral(), ErrorCode::NativeCollateralOnly);
+        require!(request.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(
+            Clock::get()?.unix_timestamp > request.reveal_end_time,
+            ErrorCode::RevealPhaseNotClosed
+        );
+        require!(ctx.accounts.ballot.vote.is_none(), ErrorCode::AlreadyRevealed);
+        require!(node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
+        require!(!node.claimed, ErrorCode::AlreadyClaimed);
+
+        // No slasher to reward: everything seized goes to the consensus ballots
+        let seized = oracle.slash_amount(node.stake);
+        slash_on_request(oracle, request, node, seized, 0, SlashReason::NoReveal)?;
+
+        emit!(NodeSlashed {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            slashed_node: node.key()
+        });
+        Ok(())
+    }
+
+    //reveal a request vote; the commitment is bound to the request instead of the oracle
+    pub fn reveal_request(ctx: Context<RevealRequest>, vote: bool, nonce: [u8; 32]) -> Result<()> {
+        let request = &mut ctx.accounts.request;
+        let ballot = &mut ctx.accounts.ballot;
+        require!(!ctx.accounts.oracle.paused, ErrorCode::Paused);
+        require!(request.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(Clock::get()?.unix_timestamp <= request.reveal_end_time, ErrorCode::RevealPhaseClosed);
+        require!(ctx.accounts.node.state != NodeState::Slashed, ErrorCode::AlreadySlashed);
+        require!(ballot.vote.is_none(), ErrorCode::AlreadyRevealed);
+        require!(
+            compute_vote_hash(&request.key(), &ballot.node, vote, &nonce, None) == ballot.vote_hash,
+            ErrorCode::InvalidReveal
+        );
+
+        ballot.vote = Some(vote);
+        if vote {
+            request.true_votes = request.true_votes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        } else {
+            request.false_votes = request.false_votes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+        Ok(())
+    }
+
+    //resolve a request by simple majority once its reveal window has closed; anyone may call it.
+    //Collateral slashed on the request is split evenly among the winning ballots, or among every
+    //revealed ballot on a tie, for each to collect with claim_ballot_reward
+    pub fn resolve_request(ctx: Context<ResolveRequest>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let request = &mut ctx.accounts.request;
+        require!(request.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+        require!(Clock::get()?.unix_timestamp > request.reveal_end_time, ErrorCode::RevealPhaseNotClosed);
+
+        request.resolution_bit = request.true_votes > request.false_votes;
+        let revealed_votes = request
+            .true_votes
+            .checked_add(request.false_votes)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let (phase, winners) = if revealed_votes == 0 {
+            (Phase::Cancelled, 0)
+        } else if request.true_votes == request.false_votes {
+            (Phase::Tied, revealed_votes)
+        } else {
+            (Phase::Complete, request.true_votes.max(request.false_votes))
+        };
+        request.phase = phase;
+
+        // Whatever doesn't divide evenly, or has nobody to go to, joins the oracle's own pool
+        request.reward_per_node = if winners > 0 { request.slashed_pool / winners } else { 0 };
+        let distributed = request.reward_per_node * winners;
+        oracle.slashed_pool = oracle
+            .slashed_pool
+            .checked_add(request.slashed_pool - distributed)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        request.slashed_pool = distributed;
+
+        emit!(RequestResolved {
+            event_version: EVENT_VERSION,
+            oracle: request.oracle,
+            request: request.key(),
+            index: request.index,
+            resolution_bit: request.resolution_bit,
+            true_votes: request.true_votes,
+            false_votes: request.false_votes,
+        });
+        Ok(())
+    }
+
+    //pay a ballot its share of the collateral slashed on its request: winning ballots of a
+    //resolved request, or every revealed ballot of a tied one
+    pub fn claim_ballot_reward(ctx: Context<ClaimBallotReward>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        let request = &mut ctx.accounts.request;
+        let ballot = &mut ctx.accounts.ballot;
+        require!(!ballot.rewarded, ErrorCode::AlreadyClaimed);
+        let vote = ballot.vote.ok_or(ErrorCode::NotConsensusNode)?;
+        let consensus = match request.phase {
+            Phase::Complete => vote == request.resolution_bit,
+            Phase::Tied => true,
+            _ => return err!(ErrorCode::InvalidPhase),
+        };
+        require!(consensus && request.reward_per_node > 0, ErrorCode::NotConsensusNode);
+
+        let reward = request.reward_per_node;
+        request.slashed_pool = request.slashed_pool.checked_sub(reward).ok_or(ErrorCode::ArithmeticOverflow)?;
+        ballot.rewarded = true;
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= reward;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += reward;
+        Ok(())
+    }
+}
+
+//hash binding an attestation to the oracle, round and outcome it reports
+pub fn attestation_binding(oracle: &Pubkey, round: u64, resolution_bit: bool, reveal_end_time: i64) -> [u8; 32] {
+    hash(
+        &[
+            oracle.as_ref(),
+            &round.to_le_bytes(),
+            &[resolution_bit as u8],
+            &reveal_end_time.to_le_bytes(),
+        ]
+        .concat(),
+    )
+    .to_bytes()
+}
+
+//whether every node the oracle still counts, less any slashed, has a standing commitment on the
+//request; nodes that left or were slashed after committing don't hold it up
+fn request_fully_committed(oracle: &Oracle, request: &Request) -> bool {
+    let live_nodes = oracle.total_nodes.saturating_sub(oracle.slashed_nodes);
+    let standing = request.committed_nodes.saturating_sub(request.slashed_nodes);
+    standing > 0 && standing >= live_nodes
+}
+
+fn start_request_reveal(oracle: &Oracle, request: &mut Request) -> Result<()> {
+    request.phase = Phase::Reveal;
+    request.reveal_end_time = Clock::get()?
+        .unix_timestamp
+        .checked_add(oracle.config.reveal_duration)
+        .ok_or(ErrorCode::InvalidDeadline)?;
+    Ok(())
+}
+
+//apply_slash for a node caught on a request; what the slash pools goes to the request's winning
+//ballots instead of the oracle's own round
+fn slash_on_request(
+    oracle: &mut Oracle,
+    request: &mut Request,
+    node: &mut Node,
+    seized: u64,
+    reward: u64,
+    reason: SlashReason,
+) -> Result<()> {
+    apply_slash(oracle, node, seized, reward, reason)?;
+    let pooled = seized - reward;
+    oracle.slashed_pool -= pooled;
+    request.slashed_pool = request.slashed_pool.checked_add(pooled).ok_or(ErrorCode::ArithmeticOverflow)?;
+    request.slashed_nodes = request.slashed_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+    Ok(())
+}
+
+//whether every node has committed; more commits than nodes means the accounting is broken
+pub fn all_committed(committed_nodes: u64, total_nodes: u64) -> Result<bool> {
+    require!(committed_nodes <= total_nodes, ErrorCode::CommitCountInvariantViolated);
+    Ok(committed_nodes >= total_nodes)
+}
+
+//seize `seized` of the node's stake into the slashed pool, less the `reward` already paid to the
+//slasher, and record both with the reason on the node so overturn_slash can reverse exactly that
+fn apply_slash(oracle: &mut Oracle, node: &mut Node, seized: u64, reward: u64, reason: SlashReason) -> Result<()> {
+    // Cap the total slashed this round (0 means uncapped)
+    let slashed_this_round = oracle.slashed_this_round.checked_add(seized).ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(
+        oracle.config.max_slash_per_round == 0 || slashed_this_round <= oracle.config.max_slash_per_round,
+        ErrorCode::SlashCapReached
+    );
+    oracle.slashed_this_round = slashed_this_round;
+    oracle.slashed_pool = seized
+        .checked_sub(reward)
+        .and_then(|pooled| oracle.slashed_pool.checked_add(pooled))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    oracle.slashed_nodes = oracle.slashed_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    node.pre_slash_state = Some(node.state);
+    node.transition(NodeState::Slashed)?;
+    node.slashed_amount = seized;
+    node.slash_reward = reward;
+    node.slash_reason = Some(reason);
+    Ok(())
+}
+
+//CPIs the oracle's slash callback program, passed as the first remaining account. A missing or
+//non-executable program only emits SlashCallbackFailed; an error inside the callback itself still
+//aborts the transaction, since the runtime cannot recover from a failed CPI
+fn notify_slash<'info>(
+    oracle: &AccountInfo<'info>,
+    node: &AccountInfo<'info>,
+    callback_program: Option<Pubkey>,
+    remaining_accounts: &[AccountInfo<'info>],
+    reason: SlashReason,
+) -> Result<()> {
+    let Some(callback_program) = callback_program else {
+        return Ok(());
+    };
+
+    let program = remaining_accounts
+        .first()
+        .filter(|info| info.key() == callback_program && info.executable);
+    let Some(program) = program else {
+        emit!(SlashCallbackFailed {
+            event_version: EVENT_VERSION,
+            oracle: oracle.key(),
+            slashed_node: node.key(),
+            callback_program,
+        });
+        return Ok(());
+    };
+
+    let notice = SlashNotice {
+        oracle: oracle.key(),
+        slashed_node: node.key(),
+        reason,
+    };
+    let ix = Instruction {
+        program_id: callback_program,
+        accounts: vec![
+            AccountMeta::new_readonly(oracle.key(), false),
+            AccountMeta::new_readonly(node.key(), false),
+        ],
+        data: notice.try_to_vec()?,
+    };
+    invoke(&ix, &[oracle.clone(), node.clone(), program.clone()])?;
+    Ok(())
+}
+
+//tallies the snapshot, pays the keeper bounty and distributes rewards to the passed nodes
+fn settle_resolution<'info>(
+    oracle: &mut Account<'info, Oracle>,
+    keeper: &AccountInfo<'info>,
+    fee_recipient: &AccountInfo<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(!oracle.is_resolved, ErrorCode::AlreadyResolved);
+    require!(oracle.phase == Phase::Reveal, ErrorCode::InvalidPhase);
+    require!(clock.unix_timestamp > oracle.reveal_end_time, ErrorCode::RevealPhaseNotClosed);
+
+    // Timestamps are validator-reported; also require real slots to have passed
+    require!(
+        clock.slot >= oracle.reveal_start_slot.saturating_add(oracle.config.min_reveal_slots),
+        ErrorCode::RevealSlotsNotElapsed
+    );
+
+    // Every passed account must be a node of this oracle, or fabricated accounts could skew payouts
+    for node_info in remaining_accounts.iter() {
+        oracle_node(oracle, node_info)?;
+    }
+
+    // Votes come from the snapshot taken at reveal, so closed node accounts still count;
+    // whatever tally_votes hasn't covered yet is tallied here
+    tally_records(oracle, usize::MAX)?;
+    let true_votes = oracle.tally_true_votes;
+    let false_votes = oracle.tally_false_votes;
+    oracle.final_true_votes = true_votes;
+    oracle.final_false_votes = false_votes;
+    oracle.final_slashed = oracle.slashed_nodes;
+    let true_weight = oracle.tally_true_weight;
+    let false_weight = oracle.tally_false_weight;
+
+    // Optionally insist on the whole committee; revealed nodes that have since closed count via the
+    // snapshot. Abstainers are passed in for their refund but are no longer part of total_nodes,
+    // and neither are nodes that left or never funded their join
+    if oracle.config.require_full_node_set {
+        let mut covered = 0;
+        for (i, node_info) in remaining_accounts.iter().enumerate() {
+            let node = oracle_node(oracle, node_info)?;
+            let committee = !matches!(
+                node.state,
+                NodeState::Abstained | NodeState::Withdrawn | NodeState::PendingJoin
+            );
+            if committee && !remaining_accounts[..i].iter().any(|info| info.key() == node_info.key()) {
+                covered += 1;
+            }
+        }
+        covered += oracle
+            .votes_snapshot
+            .iter()
+            .filter(|record| !remaining_accounts.iter().any(|info| info.key() == record.node))
+            .count() as u64;
+        require!(covered == oracle.total_nodes, ErrorCode::IncompleteNodeSet);
+    }
+
+    // Pay the keeper that triggered resolution, topped up by this round's commit fees
+    let resolve_bounty = oracle.config.resolve_bounty.checked_add(oracle.bounty_pool).ok_or(ErrorCode::ArithmeticOverflow)?;
+    oracle.bounty_pool = 0;
+    **oracle.to_account_info().try_borrow_mut_lamports()? -= resolve_bounty;
+    **keeper.try_borrow_mut_lamports()? += resolve_bounty;
+
+    // Nobody revealed and there is no fallback outcome: cancel so nodes can be refunded
+    let revealed_votes = true_votes.checked_add(false_votes).ok_or(ErrorCode::ArithmeticOverflow)?;
+    if revealed_votes == 0 && oracle.config.no_reveal_default.is_none() {
+        oracle.phase = Phase::Cancelled;
+        return Ok(());
+    }
+
+    // Without a tie-break policy an exact binary tie stays unresolved rather than defaulting to false
+    let binary = oracle.config.num_bits == 0 && !oracle.multi_outcome();
+    let adjudicated = oracle.adjudicated_bit.is_some();
+    if binary
+        && !adjudicated
+        && revealed_votes > 0
+        && true_weight == false_weight
+        && oracle.config.tie_break_policy == TieBreakPolicy::Unresolved
+    {
+        oracle.phase = Phase::Tied;
+        return share_pool_among_revealers(oracle, revealed_votes);
+    }
+
+    // A winning side short of the quorum leaves the oracle inconclusive, refunded like a tie
+    let quorum_bps = oracle.config.quorum_bps as u128;
+    let winning_weight = true_weight.max(false_weight) as u128;
+    let total_weight = true_weight as u128 + false_weight as u128;
+    if binary && !adjudicated && revealed_votes > 0 && quorum_bps > 0 && winning_weight * 10_000 < quorum_bps * total_weight {
+        oracle.phase = Phase::Inconclusive;
+        return share_pool_among_revealers(oracle, revealed_votes);
+    }
+
+    // Many nodes run by one authority count once towards the distinct voter minimum
+    require!(
+        oracle.tally_authorities >= oracle.config.min_distinct_authorities,
+        ErrorCode::InsufficientDistinctVoters
+    );
+
+    oracle.resolution_bit = match oracle.config.no_reveal_default {
+        Some(bit) if revealed_votes == 0 => bit,
+        _ if true_weight == false_weight => match oracle.config.tie_break_policy {
+            TieBreakPolicy::Fixed(bit) => bit,
+            TieBreakPolicy::RandomTieBreak => {
+                random_tie_break_bit(&oracle.key(), oracle.round, oracle.reveal_end_time)
+            }
+            // Only bitmask and multi-outcome oracles get here, and they resolve below
+            TieBreakPolicy::Unresolved => false,
+        },
+        _ => true_weight > false_weight,
+    };
+    // An adjudicated answer stands regardless of how the vote went
+    if let Some(bit) = oracle.adjudicated_bit {
+        oracle.resolution_bit = bit;
+    }
+    oracle.resolution_mask = oracle.resolution_bit as u64;
+    if oracle.config.num_bits > 0 && revealed_votes > 0 {
+        // Each question resolves independently by simple majority, ties resolving to 0
+        oracle.resolution_mask = majority_mask(&oracle.votes_snapshot, oracle.config.num_bits);
+        oracle.resolution_bit = oracle.resolution_mask & 1 == 1;
+    }
+    oracle.resolution_outcome = oracle.resolution_bit as u8;
+    if oracle.multi_outcome() && revealed_votes > 0 {
+        oracle.resolution_outcome = plurality_outcome(&oracle.votes_snapshot, oracle.config.outcome_count);
+        oracle.resolution_mask = oracle.resolution_outcome as u64;
+        oracle.resolution_bit = oracle.resolution_outcome == 1;
+    }
+
+    // With auto_settle, losing revealers get their whole stake back in the same pass
+    let auto_settle = oracle.config.auto_settle;
+    let loser_refund = |stake: u64| if auto_settle { stake } else { 0 };
+
+    // The protocol fee comes off the redistributed collateral before anyone is paid
+    let fee = protocol_fee(oracle);
+    oracle.slashed_pool -= fee;
+    **oracle.to_account_info().try_borrow_mut_lamports()? -= fee;
+    **fee_recipient.try_borrow_mut_lamports()? += fee;
+
+    oracle.reward_pool = oracle.slashed_pool;
+    price_rewards(oracle)?;
+    let loser_rebate = oracle.loser_rebate;
+    let adjustments = late_reveal_adjustments(oracle)?;
+
+    // Token collateral stays in the vault until each node calls reclaim_collateral. Under a
+    // dispute window revealers are held too, so an upheld dispute can still re-price them
+    let pays_lamports = !oracle.token_collateral();
+    let held = oracle.config.dispute_window > 0;
+    for node_info in remaining_accounts.iter().filter(|_| pays_lamports) {
+        let mut node = Account::<Node>::try_from(node_info)?;
+        if node.state == NodeState::Slashed || node.rewarded {
+            continue;
+        }
+        // Abstainers are outside the pool and simply get their stake back
+        let payout = match node.vote {
+            _ if node.state == NodeState::Abstained => node.stake,
+            Some(_) if held => continue,
+            Some(mask) if oracle.is_consensus(mask) && oracle.config.pull_rewards => continue,
+            Some(mask) if oracle.is_consensus(mask) => consensus_payout(oracle, &node_info.key(), adjustments)?,
+            Some(_) => loser_refund(node.stake).checked_add(loser_rebate).ok_or(ErrorCode::ArithmeticOverflow)?,
+            None => continue,
+        };
+        **node_info.try_borrow_mut_lamports()? += payout;
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= payout;
+        node.rewarded = true;
+        node.exit(&crate::ID)?;
+    }
+
+    // Pay the transition bonus to the node whose commit opened the reveal phase
+    if let Some(transition_node) = oracle.transition_node {
+        let transition_bonus = oracle.config.transition_bonus;
+        if let Some(node_info) = remaining_accounts.iter().find(|info| info.key() == transition_node) {
+            let node = Account::<Node>::try_from(node_info)?;
+            if node.state != NodeState::Slashed {
+                **node_info.try_borrow_mut_lamports()? += transition_bonus;
+                **oracle.to_account_info().try_borrow_mut_lamports()? -= transition_bonus;
+            }
+        }
+    }
+
+    // The oracle must stay rent exempt after paying out, or it could be purged
+    let oracle_info = oracle.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(oracle_info.data_len());
+    require!(oracle_info.lamports() >= rent_floor, ErrorCode::WouldBreakRentExemption);
+
+    // Only marked resolved once every payout above has gone through
+    oracle.is_resolved = true;
+    oracle.resolved_at = Clock::get()?.unix_timestamp;
+    oracle.dispute_end_time = oracle
+        .resolved_at
+        .checked_add(oracle.config.dispute_window)
+        .ok_or(ErrorCode::InvalidDeadline)?;
+    oracle.phase = if oracle.config.dispute_window > 0 {
+        Phase::Disputable
+    } else {
+        Phase::Complete
+    };
+
+    emit!(OracleResolved {
+        event_version: EVENT_VERSION,
+        oracle: oracle.key(),
+        resolution_bit: oracle.resolution_bit,
+        true_votes,
+        false_votes,
+    });
+    Ok(())
+}
+
+//loads a remaining account as a node, requiring it to be program-owned and joined to this oracle
+fn oracle_node<'info>(
+    oracle: &Account<'info, Oracle>,
+    node_info: &'info AccountInfo<'info>,
+) -> Result<Account<'info, Node>> {
+    require!(node_info.owner == &crate::ID, ErrorCode::ForeignNodeAccount);
+    let node = Account::<Node>::try_from(node_info)?;
+    require!(node.oracle == oracle.key(), ErrorCode::NodeOracleMismatch);
+    Ok(node)
+}
+
+//latest time the authority may resolve an authority-gated oracle
+fn resolve_deadline(oracle: &Oracle) -> Result<i64> {
+    oracle
+        .reveal_end_time
+        .checked_add(oracle.config.resolve_deadline)
+        .ok_or_else(|| ErrorCode::InvalidDeadline.into())
+}
+
+//moves the oracle into the commit phase for a new request
+fn open_commit(oracle: &mut Account<Oracle>) -> Result<()> {
+    require!(oracle.total_nodes >= oracle.config.min_nodes, ErrorCode::NotEnoughNodes);
+    oracle.phase = Phase::Commit;
+    oracle.committed_nodes = 0;
+    oracle.revealed_nodes = 0;
+    oracle.request_start_time = Clock::get()?.unix_timestamp;
+    oracle.commit_end_time = oracle
+        .request_start_time
+        .checked_add(oracle.config.commit_duration)
+        .ok_or(ErrorCode::InvalidDeadline)?;
+
+    emit!(RequestStarted {
+        event_version: EVENT_VERSION,
+        oracle: oracle.key(),
+        round: oracle.round,
+        request_start_time: oracle.request_start_time,
+        total_nodes: oracle.total_nodes,
+    });
+    Ok(())
+}
+
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+
+//aggregate price from a Pyth v2 price account, rejecting feeds that aren't currently trading
+fn read_pyth_price(data: &[u8]) -> Result<i64> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+    };
+    require!(read_u32(0) == Some(PYTH_MAGIC), ErrorCode::InvalidPriceAccount);
+    require!(read_u32(8) == Some(PYTH_PRICE_ACCOUNT_TYPE), ErrorCode::InvalidPriceAccount);
+    require!(
+        read_u32(PYTH_AGG_STATUS_OFFSET) == Some(PYTH_STATUS_TRADING),
+        ErrorCode::InvalidPriceAccount
+    );
+    let price = data[PYTH_AGG_PRICE_OFFSET..PYTH_AGG_PRICE_OFFSET + 8].try_into().unwrap();
+    Ok(i64::from_le_bytes(price))
+}
+
+//adds up to `count` untallied snapshot records to the oracle's running totals. tallied_nodes is
+//the cursor into votes_snapshot, so no record is counted twice across tally_votes calls
+fn tally_records(oracle: &mut Oracle, count: usize) -> Result<()> {
+    let start = oracle.tallied_nodes as usize;
+    let end = oracle.votes_snapshot.len().min(start.saturating_add(count));
+    for i in start..end {
+        let record = oracle.votes_snapshot[i];
+        let weight = if oracle.config.stake_weighted { record.stake } else { 1 };
+        if record.vote {
+            oracle.tally_true_votes = oracle.tally_true_votes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+            oracle.tally_true_weight = oracle.tally_true_weight.checked_add(weight).ok_or(ErrorCode::ArithmeticOverflow)?;
+        } else {
+            oracle.tally_false_votes = oracle.tally_false_votes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+            oracle.tally_false_weight = oracle.tally_false_weight.checked_add(weight).ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+        if !oracle.votes_snapshot[..i].iter().any(|earlier| earlier.authority == record.authority) {
+            oracle.tally_authorities = oracle.tally_authorities.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+    }
+    oracle.tallied_nodes = end as u64;
+    Ok(())
+}
+
+//moves the oracle into the reveal phase and fixes the reveal deadline; the only place
+//Phase::Reveal is entered, so reveal_end_time is always set alongside it
+fn start_reveal(oracle: &mut Oracle) -> Result<()> {
+    let clock = Clock::get()?;
+    oracle.phase = Phase::Reveal;
+    oracle.reveal_started_at = clock.unix_timestamp;
+    oracle.reveal_extension = 0;
+    oracle.paused_duration = 0;
+    oracle.extensions_used = 0;
+    oracle.recompute_deadline()?;
+    oracle.reveal_start_slot = clock.slot;
+    oracle.reveal_seed = hash(
+        &[
+            crate::ID.as_ref(),
+            &oracle.round.to_le_bytes(),
+            &clock.slot.to_le_bytes(),
+            &oracle.reveal_end_time.to_le_bytes(),
+        ]
+        .concat(),
+    )
+    .to_bytes();
+    Ok(())
+}
+
+//reveal slot of `node` among `slots`, fixed by the seed recorded when the reveal phase started
+pub fn reveal_slot(seed: &[u8; 32], node: &Pubkey, slots: u64) -> u64 {
+    let digest = hash(&[seed.as_ref(), node.as_ref()].concat()).to_bytes();
+    u64::from_le_bytes(digest[..8].try_into().unwrap()) % slots.max(1)
+}
+
+//with ordered_reveal, nodes may only reveal inside their own slice of the reveal window
+fn check_reveal_slot(oracle: &Oracle, node: &Pubkey) -> Result<()> {
+    if !oracle.config.ordered_reveal {
+        return Ok(());
+    }
+    let slots = oracle.total_nodes.max(1);
+    // Slots split the window as it now stands, extensions and paused time included
+    let window = oracle
+        .reveal_end_time
+        .checked_sub(oracle.reveal_started_at)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let slot_count = i64::try_from(slots).map_err(|_| ErrorCode::ArithmeticOverflow)?;
+    let slot_seconds = (window / slot_count).max(1);
+    let slot = reveal_slot(&oracle.reveal_seed, node, slots) as i64;
+    let opens = slot
+        .checked_mul(slot_seconds)
+        .and_then(|offset| oracle.reveal_started_at.checked_add(offset))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let closes = opens.checked_add(slot_seconds).ok_or(ErrorCode::ArithmeticOverflow)?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= opens && now < closes, ErrorCode::RevealOutOfSlot);
+    Ok(())
+}
+
+//reveal deadline from its components: when the reveal phase began, its configured length,
+//any extensions granted and the time spent paused
+pub fn reveal_deadline(started_at: i64, reveal_duration: i64, extension: i64, paused_duration: i64) -> Result<i64> {
+    started_at
+        .checked_add(reveal_duration)
+        .and_then(|deadline| deadline.checked_add(extension))
+        .and_then(|deadline| deadline.checked_add(paused_duration))
+        .ok_or_else(|| ErrorCode::InvalidDeadline.into())
+}
+
+//reputation of `authority`, or 0 if its reputation PDA wasn't passed or was never created
+fn reputation_score(info: Option<&AccountInfo>, authority: &Pubkey) -> Result<u64> {
+    let Some(info) = info else {
+        return Ok(0);
+    };
+    let (expected, _) = Pubkey::find_program_address(&[b"reputation", authority.as_ref()], &crate::ID);
+    require!(info.key() == expected, ErrorCode::InvalidReputationAccount);
+    if info.owner != &crate::ID {
+        return Ok(0);
+    }
+    let reputation = Reputation::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    Ok(reputation.score)
+}
+
+//checks shared by every way into `oracle`: it is unpaused, taking joins and has room, `authority`
+//is past its rejoin cooldown and, on gated oracles, has the reputation read from `reputation`
+fn check_join(
+    oracle: &Account<Oracle>,
+    membership: &mut Membership,
+    authority: Pubkey,
+    reputation: Option<&AccountInfo>,
+) -> Result<()> {
+    require!(!oracle.paused, ErrorCode::Paused);
+    require!(
+        oracle.phase == Phase::Precommit || oracle.phase == Phase::Commit,
+        ErrorCode::InvalidPhaseForJoining
+    );
+    require!(
+        oracle.total_nodes < oracle.config.max_nodes,
+        ErrorCode::MaxNodesReached
+    );
+    check_rejoin_cooldown(membership, oracle, authority)?;
+    if oracle.config.min_reputation_to_join > 0 {
+        let score = reputation_score(reputation, &authority)?;
+        require!(
+            score >= oracle.config.min_reputation_to_join,
+            ErrorCode::InsufficientReputation
+        );
+    }
+    Ok(())
+}
+
+//clears a node account for a fresh join of `authority` to `oracle`; state and stake are set by the caller
+fn reset_node(node: &mut Node, authority: Pubkey, oracle: Pubkey) {
+    node.authority = authority;
+    node.oracle = oracle;
+    node.vote_hash = None;
+    node.vote = None;
+    node.rewarded = false;
+    node.sealed_vote = None;
+    node.slashed_by = None;
+    node.reputation_recorded = false;
+    node.slash_witnesses = Vec::new();
+    node.claimed = false;
+    node.committed_at = 0;
+    node.revealed_at = 0;
+    node.operator_recorded = false;
+    node.slashed_amount = 0;
+    node.slash_reward = 0;
+    node.slash_reason = None;
+    node.pre_slash_state = None;
+}
+
+//records membership of `authority` in `oracle`, rejecting rejoins inside the cooldown
+fn check_rejoin_cooldown(membership: &mut Membership, oracle: &Account<Oracle>, authority: Pubkey) -> Result<()> {
+    if let Some(left_at) = membership.left_at {
+        let rejoin_at = left_at
+            .checked_add(oracle.config.rejoin_cooldown)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            Clock::get()?.unix_timestamp >= rejoin_at,
+            ErrorCode::RejoinCooldownActive
+        );
+    }
+    membership.authority = authority;
+    membership.oracle = oracle.key();
+    Ok(())
+}
+
+//commitment a node submits for `vote`; with bind_start_time it is only valid for
+//the request started at oracle.request_start_time
+fn vote_commitment(oracle: &Account<Oracle>, node: &Pubkey, vote: bool, nonce: &[u8; 32]) -> [u8; 32] {
+    compute_vote_hash(&oracle.key(), node, vote, nonce, bound_start_time(oracle))
+}
+
+//commitment for an outcome index; a binary vote is outcome 0 or 1. The preimage starts with the
+//domain tag, oracle and node keys so a commitment is only valid for one node of one oracle
+fn outcome_commitment(oracle: &Account<Oracle>, node: &Pubkey, outcome: u8, nonce: &[u8; 32]) -> [u8; 32] {
+    commitment_hash(oracle, node, &[outcome], nonce)
+}
+
+//commitment for a bitmask reveal, bound like vote_commitment
+fn mask_commitment(oracle: &Account<Oracle>, node: &Pubkey, mask: u64, nonce: &[u8; 32]) -> [u8; 32] {
+    commitment_hash(oracle, node, &mask.to_le_bytes(), nonce)
+}
+
+fn commitment_hash(oracle: &Account<Oracle>, node: &Pubkey, answer: &[u8], nonce: &[u8; 32]) -> [u8; 32] {
+    compute_commitment(&oracle.key(), node, answer, nonce, bound_start_time(oracle))
+}
+
+//request start folded into commitments, if the oracle binds them to it
+fn bound_start_time(oracle: &Oracle) -> Option<i64> {
+    oracle.config.bind_start_time.then_some(oracle.request_start_time)
+}
+
+//the hash a node commits for a binary vote, exactly as commit, reveal and slash_colluding check it.
+//pass request_start_time only for oracles with bind_start_time set
+pub fn compute_vote_hash(
+    oracle: &Pubkey,
+    node: &Pubkey,
+    vote: bool,
+    nonce: &[u8; 32],
+    request_start_time: Option<i64>,
+) -> [u8; 32] {
+    compute_commitment(oracle, node, &[vote as u8], nonce, request_start_time)
+}
+
+//domain tag ++ oracle ++ node ++ answer ++ nonce, plus the request start when it is bound
+pub fn compute_commitment(
+    oracle: &Pubkey,
+    node: &Pubkey,
+    answer: &[u8],
+    nonce: &[u8; 32],
+    request_start_time: Option<i64>,
+) -> [u8; 32] {
+    let start_time = request_start_time.map(i64::to_le_bytes);
+    let mut preimage = vec![COMMITMENT_DOMAIN, oracle.as_ref(), node.as_ref(), answer, &nonce[..]];
+    if let Some(start_time) = start_time.as_ref() {
+        preimage.push(start_time);
+    }
+    hash(&preimage.concat()).to_bytes()
+}
+
+//per-bit majority over the revealed masks; a bit is set only if more than half set it
+pub fn majority_mask(records: &[VoteRecord], num_bits: u8) -> u64 {
+    (0..num_bits as u32).fold(0, |resolution, bit| {
+        let ones = records.iter().filter(|record| record.mask >> bit & 1 == 1).count();
+        if ones * 2 > records.len() {
+            resolution | 1 << bit
+        } else {
+            resolution
+        }
+    })
+}
+
+//outcome with the most reveals; ties go to the lowest index
+pub fn plurality_outcome(records: &[VoteRecord], outcome_count: u8) -> u8 {
+    let mut tally = vec![0u64; outcome_count as usize];
+    for record in records.iter() {
+        tally[record.mask as usize] += 1;
+    }
+    (0..outcome_count).fold(0, |best, outcome| {
+        if tally[outcome as usize] > tally[best as usize] {
+            outcome
+        } else {
+            best
+        }
+    })
+}
+
+//checks a revealed vote against the node's commitment and records it
+fn record_reveal(oracle: &mut Account<Oracle>, node: &mut Account<Node>, vote: bool, nonce: &[u8; 32]) -> Result<()> {
+    let required_stake = if vote {
+        oracle.config.true_reveal_stake
+    } else {
+        oracle.config.false_reveal_stake
+    };
+    require!(node.stake >= required_stake, ErrorCode::InsufficientStake);
+
+    let commitment = vote_commitment(oracle, &node.key(), vote, nonce);
+    finish_reveal(oracle, node, commitment, nonce, vote as u64)
+}
+
+//records a reveal whose preimage hashes to `commitment`; `mask` is the revealed answers,
+//with a single-question vote stored as bit 0
+fn finish_reveal(
+    oracle: &mut Oracle,
+    node: &mut Account<Node>,
+    commitment: [u8; 32],
+    nonce: &[u8; 32],
+    mask: u64,
+) -> Result<()> {
+    require!(node.state != NodeState::Abstained, ErrorCode::NodeAbstained);
+    let vote_hash = node.vote_hash.ok_or(ErrorCode::NotCommitted)?;
+    require!(commitment == vote_hash, ErrorCode::InvalidReveal);
+
+    if oracle.config.unique_nonces_required {
+        let fingerprint = nonce_fingerprint(nonce);
+        require!(
+            !oracle.revealed_nonces.contains(&fingerprint),
+            ErrorCode::DuplicateNonce
+        );
+        oracle.revealed_nonces.push(fingerprint);
+    }
+
+    let vote = mask & 1 == 1;
+    node.transition(NodeState::Revealed)?;
+    node.vote = Some(mask);
+    node.revealed_at = Clock::get()?.unix_timestamp;
+    oracle.votes_snapshot.push(VoteRecord {
+        node: node.key(),
+        authority: node.authority,
+        vote,
+        mask,
+        revealed_at: node.revealed_at,
+        stake: node.stake,
+    });
+    oracle.revealed_nodes = oracle.revealed_nodes.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit!(VoteRevealed {
+        event_version: EVENT_VERSION,
+        oracle: node.oracle,
+        node: node.key(),
+        vote,
+        mask,
+    });
+
+    Ok(())
+}
+
+//XORs vote ++ nonce with a pad derived from the escrowed key and node; seals and unseals alike
+pub fn sealed_vote_ciphertext(data: &[u8; 33], key: &[u8; 32], node: &Pubkey) -> [u8; 33] {
+    let head = hash(&[key.as_ref(), node.as_ref(), &[0]].concat()).to_bytes();
+    let tail = hash(&[key.as_ref(), node.as_ref(), &[1]].concat()).to_bytes();
+    let mut out = *data;
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte ^= if i < 32 { head[i] } else { tail[0] };
+    }
+    out
+}
+
+//compact identifier for a revealed nonce, used to detect reuse across nodes
+fn nonce_fingerprint(nonce: &[u8; 32]) -> u64 {
+    let digest = hash(nonce).to_bytes();
+    u64::from_le_bytes(digest[..8].try_into().unwrap())
+}
+
+//tie-break bit for RandomTieBreak, derived from hash(oracle ++ round ++ reveal_end_time);
+//unpredictable until the reveal deadline is fixed, and verifiable by anyone afterwards
+pub fn random_tie_break_bit(oracle: &Pubkey, round: u64, reveal_end_time: i64) -> bool {
+    let digest = hash(&[oracle.as_ref(), &round.to_le_bytes(), &reveal_end_time.to_le_bytes()].concat());
+    digest.to_bytes()[0] & 1 == 1
+}
+
+//(owner token account, vault, vault authority, token program) for token collateral transfers,
+//plus the vault authority's bump
+fn token_accounts<'info>(
+    oracle: &Account<'info, Oracle>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<([AccountInfo<'info>; 4], u8)> {
+    let [owner_token, vault, vault_authority, token_program, ..] = accounts else {
+        return err!(ErrorCode::InvalidTokenAccounts);
+    };
+    let (expected_authority, bump) =
+        Pubkey::find_program_address(&[b"vault", oracle.key().as_ref()], &crate::ID);
+    require!(
+        vault.key() == oracle.vault
+            && vault_authority.key() == expected_authority
+            && token_program.key() == token::ID,
+        ErrorCode::InvalidTokenAccounts
+    );
+    Ok((
+        [owner_token.clone(), vault.clone(), vault_authority.clone(), token_program.clone()],
+        bump,
+    ))
+}
+
+//pay `amount` of collateral out of the oracle: lamports straight to `recipient`, or for token
+//collateral a vault transfer signed by the vault authority to the owner token account leading `accounts`
+fn pay_collateral<'info>(
+    oracle: &Account<'info, Oracle>,
+    recipient: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    if !oracle.token_collateral() {
+        **oracle.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **recipient.try_borrow_mut_lamports()? += amount;
+        return Ok(());
+    }
+    let ([owner_token, vault, vault_authority, token_program], bump) = token_accounts(oracle, accounts)?;
+    let oracle_key = oracle.key();
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program,
+            token::Transfer {
+                from: vault,
+                to: owner_token,
+                authority: vault_authority,
+            },
+            &[&[b"vault", oracle_key.as_ref(), &[bump]]],
+        ),
+        amount,
+    )
+}
+
+//a Tied or Inconclusive round has no winners, so the slashed pool is split evenly across the
+//revealers, each collecting its share with its refund through claim_abandoned
+fn share_pool_among_revealers(oracle: &mut Oracle, revealed_votes: u64) -> Result<()> {
+    if revealed_votes == 0 {
+        return Ok(());
+    }
+    oracle.refund_share = oracle.slashed_pool / revealed_votes;
+    oracle.slashed_pool -= oracle.refund_share * revealed_votes;
+    Ok(())
+}
+
+//split reward_pool across the current consensus side: evenly, or by stake under StakeProportional,
+//less the rebate owed to honest losers. The indivisible remainder is left in slashed_pool to carry
+//forward. settle_dispute runs this again when an upheld dispute changes the consensus side
+fn price_rewards(oracle: &mut Oracle) -> Result<()> {
+    let slashed_pool = oracle.reward_pool;
+    let revealed_votes = oracle
+        .final_true_votes
+        .checked_add(oracle.final_false_votes)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let consensus_nodes = oracle
+        .votes_snapshot
+        .iter()
+        .filter(|record| oracle.is_consensus(record.mask))
+        .count() as u64;
+    let losing_nodes = revealed_votes.saturating_sub(consensus_nodes);
+
+    let mut distributed = 0u64;
+    if oracle.stake_proportional() {
+        oracle.consensus_stake = oracle
+            .votes_snapshot
+            .iter()
+            .filter(|record| oracle.is_consensus(record.mask))
+            .try_fold(0u64, |total, record| total.checked_add(record.stake))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        oracle.stake_reward_pool = slashed_pool;
+        for record in oracle.votes_snapshot.iter().filter(|record| oracle.is_consensus(record.mask)) {
+            distributed = oracle
+                .reward_for(record.stake)?
+                .checked_sub(record.stake)
+                .and_then(|share| distributed.checked_add(share))
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+    } else if consensus_nodes > 0 {
+        distributed = slashed_pool / consensus_nodes * consensus_nodes;
+    }
+    oracle.slashed_pool = slashed_pool.checked_sub(distributed).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    let (reward_per_node, loser_rebate) = split_loser_rebate(
+        reward_per_node(oracle.config.collateral, slashed_pool, consensus_nodes)?,
+        consensus_nodes,
+        losing_nodes,
+        oracle.config.loser_rebate_bps,
+    )?;
+    oracle.reward_per_node = reward_per_node;
+    oracle.loser_rebate = loser_rebate;
+    Ok(())
+}
+
+//the protocol fee resolve takes from the slashed pool; token pools stay in the vault and are not charged
+fn protocol_fee(oracle: &Oracle) -> u64 {
+    if oracle.token_collateral() {
+        return 0;
+    }
+    apply_bps(oracle.slashed_pool, oracle.config.fee_bps)
+}
+
+//whether a record was revealed inside late_reveal_window
+fn revealed_late(oracle: &Oracle, record: &VoteRecord) -> Result<bool> {
+    let late_window = oracle.config.late_reveal_window;
+    let late_from = oracle
+        .reveal_end_time
+        .checked_sub(late_window)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    Ok(late_window > 0 && record.revealed_at > late_from)
+}
+
+//penalty withheld from each consensus node revealing inside late_reveal_window, and the bonus
+//each earlier one gets from those penalties; priced after price_rewards
+fn late_reveal_adjustments(oracle: &Oracle) -> Result<(u64, u64)> {
+    let mut late_nodes = 0u64;
+    let mut early_nodes = 0u64;
+    for record in oracle.votes_snapshot.iter().filter(|record| oracle.is_consensus(record.mask)) {
+        if revealed_late(oracle, record)? {
+            late_nodes += 1;
+        } else {
+            early_nodes += 1;
+        }
+    }
+    if late_nodes == 0 || early_nodes == 0 {
+        return Ok((0, 0));
+    }
+    let penalty = apply_bps(oracle.reward_per_node, oracle.config.late_reveal_penalty_bps);
+    let bonus = u64::try_from(penalty as u128 * late_nodes as u128 / early_nodes as u128)
+        .map_err(|_| ErrorCode::ArithmeticOverflow)?;
+    Ok((penalty, bonus))
+}
+
+//what resolve pays a consensus node: its priced reward, shifted by `adjustments` from
+//late_reveal_adjustments unless rewards are stake_proportional
+fn consensus_payout(oracle: &Oracle, node: &Pubkey, adjustments: (u64, u64)) -> Result<u64> {
+    let reward = oracle.reward_for_node(node)?;
+    if oracle.stake_proportional() {
+        return Ok(reward);
+    }
+    let (late_penalty, early_bonus) = adjustments;
+    let late = match oracle.votes_snapshot.iter().find(|record| record.node == *node) {
+        Some(record) => revealed_late(oracle, record)?,
+        None => false,
+    };
+    let payout = if late {
+        reward.checked_sub(late_penalty)
+    } else {
+        reward.checked_add(early_bonus)
+    };
+    payout.ok_or_else(|| ErrorCode::ArithmeticOverflow.into())
+}
+
+//a consensus node's own deposit plus its even share of the slashed pool
+pub fn reward_per_node(collateral: u64, slashed_pool: u64, consensus_nodes: u64) -> Result<u64> {
+    if consensus_nodes == 0 {
+        return Ok(0);
+    }
+    collateral
+        .checked_add(slashed_pool / consensus_nodes)
+        .ok_or_else(|| ErrorCode::ArithmeticOverflow.into())
+}
+
+//moves loser_rebate_bps of each winner's reward into an even rebate for losing revealers,
+//returning (net reward per winner, rebate per loser); rounding dust stays in the pool
+pub fn split_loser_rebate(reward_per_node: u64, winners: u64, losers: u64, loser_rebate_bps: u16) -> Result<(u64, u64)> {
+    if losers == 0 || loser_rebate_bps == 0 {
+        return Ok((reward_per_node, 0));
+    }
+    let deduction = apply_bps(reward_per_node, loser_rebate_bps);
+    let rebate = u64::try_from(deduction as u128 * winners as u128 / losers as u128)
+        .map_err(|_| ErrorCode::ArithmeticOverflow)?;
+    Ok((reward_per_node - deduction, rebate))
+}
+
+//`bps` basis points of `amount`, multiplied in u128 so large amounts can't overflow; bps <= 10_000
+//keeps the result within `amount`
+pub fn apply_bps(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / 10_000) as u64
+}
+
+//how resolve settles an exact tie between true and false votes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreakPolicy {
+    Fixed(bool),
+    RandomTieBreak,
+    //leave the oracle in Phase::Tied; nodes reclaim their stake through claim_abandoned
+    Unresolved,
+}
+
+impl Default for TieBreakPolicy {
+    fn default() -> Self {
+        TieBreakPolicy::Unresolved
+    }
+}
+
+//how resolve shares the slashed pool among consensus nodes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RewardPolicy {
+    //every consensus node gets collateral plus an equal share
+    EqualSplit,
+    //every consensus node gets its stake plus a share pro rata to it
+    StakeProportional,
+}
+
+impl Default for RewardPolicy {
+    fn default() -> Self {
+        RewardPolicy::EqualSplit
+    }
+}
+
+//lifecycle of a node; vote_hash and vote hold the values, this is the source of truth for state
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeState {
+    PendingJoin,
+    Joined,
+    Committed,
+    Revealed,
+    Slashed,
+    Withdrawn,
+    Abstained,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Precommit,
+    Commit,
+    Reveal,
+    Complete,
+    Cancelled,
+    //an exact tie under TieBreakPolicy::Unresolved; distinct from a genuine false resolution
+    Tied,
+    //resolved but still open to raise_dispute until dispute_end_time; finalize moves it to Complete
+    Disputable,
+    //the winning side fell short of quorum_bps; nodes reclaim their stake through claim_abandoned
+    Inconclusive,
+}
+
+//oracle parameters fixed at initialize
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OracleConfig {
+    pub collateral: u64,
+    pub reveal_duration: i64,
+    pub max_nodes: u64,
+    //minimum reveal_duration per node in max_nodes, 0 disables the check
+    pub min_reveal_seconds_per_node: i64,
+    //seconds after initialize before an unstarted oracle can be abandoned, 0 disables
+    pub abandon_timeout: i64,
+    //stake a node must hold to reveal each outcome
+    pub true_reveal_stake: u64,
+    pub false_reveal_stake: u64,
+    pub tie_break_policy: TieBreakPolicy,
+    //cap on total collateral slashed per round, 0 means uncapped
+    pub max_slash_per_round: u64,
+    //paid to whoever calls resolve, funded by the authority at initialize
+    pub resolve_bounty: u64,
+    //include request_start_time in the commitment preimage
+    pub bind_start_time: bool,
+    //reject reveals reusing a nonce another node already revealed
+    pub unique_nonces_required: bool,
+    //outcome when nobody reveals; None cancels the oracle so nodes can claim refunds
+    pub no_reveal_default: Option<bool>,
+    //seconds an authority that left must wait before rejoining
+    pub rejoin_cooldown: i64,
+    //join_network only records the node; collateral is posted later via fund_join
+    pub deferred_funding: bool,
+    //paid at resolve to the node whose commit triggered the reveal phase, funded at initialize
+    pub transition_bonus: u64,
+    //seconds after resolve during which the result can be disputed, 0 allows immediate finalize
+    pub dispute_window: i64,
+    //lamports a disputer must post to raise a dispute
+    pub dispute_bond: u64,
+    //seconds before reveal_end_time from which sealed votes can be auto revealed
+    pub auto_reveal_window: i64,
+    //distinct node authorities that must have revealed for resolve to succeed
+    pub min_distinct_authorities: u64,
+    //lamports a slasher posts with each slash, forfeited to the node if the slash is overturned
+    pub slash_bond: u64,
+    //slots that must pass after the reveal phase starts before resolve, 0 disables
+    pub min_reveal_slots: u64,
+    //program notified by CPI whenever a node is slashed
+    pub slash_callback_program: Option<Pubkey>,
+    //share of each winner's reward redistributed evenly to non-slashed losing revealers
+    pub loser_rebate_bps: u16,
+    //price condition that lets anyone start the request through try_trigger
+    pub price_trigger: Option<PriceTrigger>,
+    //reputation an authority needs to join, 0 lets anyone join
+    pub min_reputation_to_join: u64,
+    //distinct slashers needed before a slash takes effect; 0 and 1 both slash immediately
+    pub required_witnesses: u8,
+    //independent yes/no questions answered with reveal_mask, 0 for a single question via reveal
+    pub num_bits: u8,
+    //bits a node must match to count as consensus, 0 requires all of them
+    pub min_matching_bits: u8,
+    //only the authority may call resolve
+    pub authority_resolves: bool,
+    //seconds after reveal_end_time the authority has to resolve, 0 for no deadline
+    pub resolve_deadline: i64,
+    //posted by the authority at initialize, refunded on a timely resolve
+    pub resolution_bond: u64,
+    //share of the bond paid to revealed nodes when resolution is late
+    pub late_penalty_bps: u16,
+    //split the reveal window into one slot per node and only accept reveals in a node's own slot
+    pub ordered_reveal: bool,
+    //resolve also refunds losing, non-slashed revealers their collateral in the same pass
+    pub auto_settle: bool,
+    //share of the reward withheld from consensus nodes revealing inside late_reveal_window
+    pub late_reveal_penalty_bps: u16,
+    //seconds before reveal_end_time that count as a late reveal, 0 disables the penalty
+    pub late_reveal_window: i64,
+    //lamports each node pays at commit towards the resolve bounty
+    pub commit_fee: u64,
+    //resolve must be passed every node in the committee
+    pub require_full_node_set: bool,
+    //seconds after start_request before the authority may cancel an incomplete commit phase, 0 disables
+    pub commit_deadline: i64,
+    //seconds the commit phase runs before anyone may close it, 0 waits for every node
+    pub commit_duration: i64,
+    //number of outcomes revealed with reveal_outcome; 0 or 2 keep the binary vote
+    pub outcome_count: u8,
+    //binary votes are weighted by node stake, and rewards are proportional to it
+    pub stake_weighted: bool,
+    //resolve may go ahead with half of the committee or fewer revealed
+    pub allow_minority_resolve: bool,
+    //nodes that must have joined before a request can start
+    pub min_nodes: u64,
+    //times per round the authority may push back the reveal deadline with extend_reveal
+    pub max_reveal_extensions: u8,
+    //share of slashed collateral paid straight to the witness completing a slash_colluding
+    pub slasher_reward_bps: u16,
+    //protocol fee taken from the redistributed collateral at resolve
+    pub fee_bps: u16,
+    //receives the protocol fee; defaults to the authority
+    pub fee_recipient: Pubkey,
+    //resolve leaves consensus rewards in the oracle for each node to claim_reward
+    pub pull_rewards: bool,
+    //share of revealed vote weight the winning side needs for a binding resolution; 0 disables
+    pub quorum_bps: u16,
+    //the authority may settle the outcome with slash_wrong_reveal, slashing revealers who got it wrong
+    pub adjudicated: bool,
+    //share of collateral slash_colluding seizes, the rest reclaimable by the node; 0 seizes it all
+    pub slash_bps: u16,
+    //stake_weighted oracles always reward StakeProportional
+    pub reward_policy: RewardPolicy,
+}
+
+impl OracleConfig {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 2 + 8 + 8 + 1 + 1 + 2 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 33 + 2 + 1 + PriceTrigger::LEN + 8 + 1 + 1 + 1 + 1 + 8 + 8 + 2 + 1 + 1 + 2 + 8 + 8 + 1 + 8 + 8 + 1 + 1 + 1 + 8 + 1 + 2 + 2 + 32 + 1 + 2 + 1 + 2 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceTrigger {
+    //Pyth price account to watch
+    pub price_account: Pubkey,
+    //in the feed's own exponent
+    pub threshold: i64,
+    //fire when the price is at or above the threshold, otherwise at or below
+    pub trigger_above: bool,
+}
+
+impl PriceTrigger {
+    pub const LEN: usize = 32 + 8 + 1;
+}
+
+#[account]
+pub struct Oracle {
+    pub authority: Pubkey,
+    pub config: OracleConfig,
+    //hash of the off-chain question text and resolution criteria; clients hash the question and compare
+    pub question_hash: [u8; 32],
+    pub is_resolved: bool,
+    pub resolution_bit: bool,
+    //per-question outcomes; equals resolution_bit in single-question oracles
+    pub resolution_mask: u64,
+    //plurality outcome index; equals resolution_bit in binary oracles
+    pub resolution_outcome: u8,
+    pub phase: Phase,
+    pub reveal_end_time: i64,
+    pub total_nodes: u64,
+    pub committed_nodes: u64,
+    //nodes that revealed this round
+    pub revealed_nodes: u64,
+    //votes_snapshot records tallied so far, and their running totals; see tally_votes
+    pub tallied_nodes: u64,
+    pub tally_true_votes: u64,
+    pub tally_false_votes: u64,
+    pub tally_true_weight: u64,
+    pub tally_false_weight: u64,
+    //distinct authorities among the tallied records
+    pub tally_authorities: u64,
+    pub created_at: i64,
+    pub round: u64,
+    pub slashed_this_round: u64,
+    pub request_start_time: i64,
+    pub reward_per_node: u64,
+    pub loser_rebate: u64,
+    //stake-weighted oracles: total stake on the winning side and the slashed collateral split across it
+    pub consensus_stake: u64,
+    pub stake_reward_pool: u64,
+    //slashed collateral left after the fee at resolve, which price_rewards splits
+    pub reward_pool: u64,
+    //each revealer's share of the slashed pool when the round ends Tied or Inconclusive
+    pub refund_share: u64,
+    //commit fees collected this round, paid to the resolving keeper
+    pub bounty_pool: u64,
+    //slashed collateral awaiting redistribution to consensus nodes, carried across rounds
+    pub slashed_pool: u64,
+    //SPL mint collateral is posted in; Pubkey::default() for native lamports
+    pub mint: Pubkey,
+    //token account holding token collateral, owned by the [b"vault", oracle] PDA
+    pub vault: Pubkey,
+    //after this, close_commit may start the reveal phase without the missing commits
+    pub commit_end_time: i64,
+    //node whose commit moved the oracle into the reveal phase
+    pub transition_node: Option<Pubkey>,
+    pub resolved_at: i64,
+    //whoever raised a dispute against the resolution, if anyone
+    pub dispute: Option<Pubkey>,
+    pub finalized_at: Option<i64>,
+    //resolved_at + dispute_window, set at resolve
+    pub dispute_end_time: i64,
+    pub reveal_start_slot: u64,
+    //components reveal_end_time is derived from, see recompute_deadline
+    pub reveal_started_at: i64,
+    pub reveal_extension: i64,
+    pub paused_duration: i64,
+    //set_paused blocks joins, votes and slashing while set
+    pub paused: bool,
+    pub paused_at: i64,
+    //extend_reveal calls this round, bounded by max_reveal_extensions
+    pub extensions_used: u8,
+    //successor named by transfer_authority, waiting for accept_authority
+    pub pending_authority: Option<Pubkey>,
+    //requests opened by create_request; the next Request is seeded with this index
+    pub request_count: u64,
+    //true outcome supplied by slash_wrong_reveal; overrides the vote count at resolve
+    pub adjudicated_bit: Option<bool>,
+    //nodes currently slashed, by any path
+    pub slashed_nodes: u64,
+    //revealed votes on each side and slashed nodes as they stood at resolve, for auditing the margin
+    pub final_true_votes: u64,
+    pub final_false_votes: u64,
+    pub final_slashed: u64,
+    //oracle this one was imported from; its nodes move over with import_node
+    pub migrated_from: Option<Pubkey>,
+    //orders reveals under ordered_reveal
+    pub reveal_seed: [u8; 32],
+    //fingerprints of revealed nonces, only kept when unique_nonces_required
+    pub revealed_nonces: Vec<u64>,
+    //every reveal in order, so resolve doesn't need each node account to still exist
+    pub votes_snapshot: Vec<VoteRecord>,
+}
+
+impl Oracle {
+    //fixed-size part of the account without the discriminator, counting only the length prefixes of the vecs
+    pub const LEN: usize = 32 + OracleConfig::LEN + 32 + 1 + 1 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 8 + 33 + 8 + 33 + 9 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 33 + 8 + 2 + 8 + 8 + 8 + 8 + 8 + 8 + 33 + 32 + 4 + 4;
+
+    //account size without the discriminator; revealed_nonces and votes_snapshot are reserved for every node up front
+    pub fn space(config: &OracleConfig) -> usize {
+        let revealed_nonces = if config.unique_nonces_required {
+            (config.max_nodes as usize).saturating_mul(8)
+        } else {
+            0
+        };
+        let votes_snapshot = (config.max_nodes as usize).saturating_mul(VoteRecord::LEN);
+        Oracle::LEN + revealed_nonces + votes_snapshot
+    }
+
+    //whether consensus nodes are paid by stake rather than an equal split
+    pub fn stake_proportional(&self) -> bool {
+        self.config.stake_weighted || self.config.reward_policy == RewardPolicy::StakeProportional
+    }
+
+    //consensus node payout: the flat reward_per_node plus any stake topped up past the
+    //collateral, or stake plus a pro-rata share of the slashed pool when stake_proportional
+    pub fn reward_for(&self, stake: u64) -> Result<u64> {
+        if !self.stake_proportional() {
+            return self
+                .reward_per_node
+                .checked_add(stake.saturating_sub(self.config.collateral))
+                .ok_or_else(|| ErrorCode::ArithmeticOverflow.into());
+        }
+        let share = self.stake_reward_pool as u128 * stake as u128 / self.consensus_stake.max(1) as u128;
+        stake.checked_add(share as u64).ok_or_else(|| ErrorCode::ArithmeticOverflow.into())
+    }
+
+    //reward for a consensus node, priced on the stake it revealed with rather than its live stake
+    pub fn reward_for_node(&self, node: &Pubkey) -> Result<u64> {
+        let record = self
+            .votes_snapshot
+            .iter()
+            .find(|record| record.node == *node)
+            .ok_or(ErrorCode::NotConsensusNode)?;
+        self.reward_for(record.stake)
+    }
+
+    //cut of the collateral seized from one node paid to its slasher
+    pub fn slasher_reward(&self, seized: u64) -> u64 {
+        apply_bps(seized, self.config.slasher_reward_bps)
+    }
+
+    //part of a node's deposit slash_colluding seizes
+    pub fn slash_amount(&self, stake: u64) -> u64 {
+        if self.config.slash_bps == 0 {
+            stake
+        } else {
+            apply_bps(stake, self.config.slash_bps)
+        }
+    }
+
+    //whether nodes reveal an outcome index rather than a binary vote
+    pub fn multi_outcome(&self) -> bool {
+        self.config.outcome_count > 2
+    }
+
+    //whether collateral is an SPL token held in `vault` rather than lamports on the oracle
+    pub fn token_collateral(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    //bits in use: num_bits questions, or just bit 0 for a single binary question
+    pub fn bits_mask(&self) -> u64 {
+        u64::MAX >> (64 - self.config.num_bits.max(1) as u32)
+    }
+
+    //whether revealed answers agree with the resolution on enough bits to share the reward
+    pub fn is_consensus(&self, mask: u64) -> bool {
+        // Outcome indices either match the plurality outcome or they don't
+        if self.multi_outcome() {
+            return mask == self.resolution_mask;
+        }
+        let required = match self.config.min_matching_bits {
+            0 => self.config.num_bits.max(1),
+            bits => bits,
+        };
+        (!(mask ^ self.resolution_mask) & self.bits_mask()).count_ones() >= required as u32
+    }
+
+    //how much further extend_reveal and pauses may still push back this round's reveal deadline
+    pub fn extension_room(&self) -> i64 {
+        MAX_REVEAL_EXTENSION
+            .saturating_sub(self.reveal_extension.saturating_add(self.paused_duration))
+            .max(0)
+    }
+
+    //the single place reveal_end_time is written once the reveal phase has started
+    pub fn recompute_deadline(&mut self) -> Result<()> {
+        self.reveal_end_time = reveal_deadline(
+            self.reveal_started_at,
+            self.config.reveal_duration,
+            self.reveal_extension,
+            self.paused_duration,
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VoteRecord {
+    pub node: Pubkey,
+    pub authority: Pubkey,
+    pub vote: bool,
+    pub mask: u64,
+    pub revealed_at: i64,
+    //node stake at reveal, the vote's weight under stake_weighted
+    pub stake: u64,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 32 + 32 + 1 + 8 + 8 + 8;
+}
+
+#[account]
+pub struct Node {
+    pub authority: Pubkey,
+    pub oracle: Pubkey,
+    pub vote_hash: Option<[u8; 32]>,
+    //revealed answer: the bit of a binary vote, an outcome index, or one bit per question
+    pub vote: Option<u64>,
+    pub state: NodeState,
+    pub stake: u64,
+    pub rewarded: bool,
+    pub sealed_vote: Option<SealedVote>,
+    pub slashed_by: Option<Pubkey>,
+    pub reputation_recorded: bool,
+    //distinct slashers that have submitted a collusion proof against this node
+    pub slash_witnesses: Vec<Pubkey>,
+    //collateral and reward withdrawn through reclaim_collateral
+    pub claimed: bool,
+    //unix timestamps of this round's commit and reveal, 0 until they happen
+    pub committed_at: i64,
+    pub revealed_at: i64,
+    //outcome already folded into the authority's Operator record
+    pub operator_recorded: bool,
+    //collateral seized when the node was slashed; the rest of its stake stays reclaimable
+    pub slashed_amount: u64,
+    //part of slashed_amount paid straight to the slasher, and why the node was slashed
+    pub slash_reward: u64,
+    pub slash_reason: Option<SlashReason>,
+    //state the slash interrupted, restored if it is overturned
+    pub pre_slash_state: Option<NodeState>,
+}
+
+//vote ++ nonce sealed for auto_reveal, plus the hash of the key escrowed to unseal it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SealedVote {
+    pub ciphertext: [u8; 33],
+    pub key_hash: [u8; 32],
+}
+
+impl Node {
+    //account size without the discriminator, with every option set and slash_witnesses full
+    pub const LEN: usize = 32 + 32 + 33 + 9 + 1 + 8 + 1 + 66 + 33 + 1 + 4 + 32 * MAX_SLASH_WITNESSES + 1 + 8 + 8 + 1 + 8 + 8 + 2 + 2;
+
+    //moves the node to `next`, rejecting transitions outside the node lifecycle
+    pub fn transition(&mut self, next: NodeState) -> Result<()> {
+        use NodeState::*;
+        let allowed = matches!(
+            (self.state, next),
+            (PendingJoin, Joined)
+                | (Joined, Committed)
+                | (Joined, Abstained)
+                | (Committed, Revealed)
+                | (Joined | Committed | Revealed, Slashed)
+                | (Joined | Committed | Revealed | Abstained, Withdrawn)
+        );
+        require!(allowed, ErrorCode::InvalidNodeState);
+        self.state = next;
+        Ok(())
+    }
+
+    //undo a slash back to the state it interrupted; outside the normal lifecycle so a slashed
+    //node can't recommit on its own
+    pub fn reinstate(&mut self) -> Result<()> {
+        require!(self.state == NodeState::Slashed, ErrorCode::InvalidNodeState);
+        self.state = self.pre_slash_state.take().ok_or(ErrorCode::InvalidNodeState)?;
+        Ok(())
+    }
+}
+
+//persists per (oracle, authority) across node accounts so leaving can't reset the cooldown
+#[account]
+pub struct Membership {
+    pub authority: Pubkey,
+    pub oracle: Pubkey,
+    pub left_at: Option<i64>,
+}
+
+//written once per round after resolution; program ownership is what makes it trustworthy
+#[account]
+pub struct Attestation {
+    pub oracle: Pubkey,
+    pub round: u64,
+    pub resolution_bit: bool,
     pub reveal_end_time: i64,
+    pub binding: [u8; 32],
+    pub attested_at: i64,
+}
+
+//an oracle in transit between program versions; state is the borsh-encoded Oracle at `version`
+#[account]
+pub struct StateExport {
+    pub version: u8,
+    pub oracle: Pubkey,
+    pub authority: Pubkey,
+    //data length of the exported oracle account, reallocated on import
+    pub space: u64,
+    //oracle lamports above its rent-exempt minimum
+    pub funds: u64,
+    pub state: Vec<u8>,
+}
+
+impl StateExport {
+    //the exported oracle in the current layout, converting version 1 exports
+    pub fn decode_state(&self) -> Result<Oracle> {
+        let state = match self.version {
+            1 => LegacyOracleV1::try_from_slice(&self.state).map(Oracle::from),
+            EXPORT_VERSION => Oracle::try_from_slice(&self.state),
+            _ => return err!(ErrorCode::UnsupportedExportVersion),
+        };
+        state.map_err(|_| ErrorCode::InvalidExportState.into())
+    }
+
+    //account size of the imported oracle; the exported size when the state doesn't decode,
+    //which import_state then rejects
+    pub fn import_space(&self) -> usize {
+        self.decode_state()
+            .map_or(self.space as usize, |state| 8 + Oracle::space(&state.config))
+    }
+}
+
+//OracleConfig as exported under version 1, before the fields appended since
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LegacyOracleConfigV1 {
+    pub collateral: u64,
     pub reveal_duration: i64,
     pub max_nodes: u64,
+    //minimum reveal_duration per node in max_nodes, 0 disables the check
+    pub min_reveal_seconds_per_node: i64,
+    //seconds after initialize before an unstarted oracle can be abandoned, 0 disables
+    pub abandon_timeout: i64,
+    //stake a node must hold to reveal each outcome
+    pub true_reveal_stake: u64,
+    pub false_reveal_stake: u64,
+    pub tie_break_policy: TieBreakPolicy,
+    //cap on total collateral slashed per round, 0 means uncapped
+    pub max_slash_per_round: u64,
+    //paid to whoever calls resolve, funded by the authority at initialize
+    pub resolve_bounty: u64,
+    //include request_start_time in the commitment preimage
+    pub bind_start_time: bool,
+    //reject reveals reusing a nonce another node already revealed
+    pub unique_nonces_required: bool,
+    //outcome when nobody reveals; None cancels the oracle so nodes can claim refunds
+    pub no_reveal_default: Option<bool>,
+    //seconds an authority that left must wait before rejoining
+    pub rejoin_cooldown: i64,
+    //join_network only records the node; collateral is posted later via fund_join
+    pub deferred_funding: bool,
+    //paid at resolve to the node whose commit triggered the reveal phase, funded at initialize
+    pub transition_bonus: u64,
+    //seconds after resolve during which the result can be disputed, 0 allows immediate finalize
+    pub dispute_window: i64,
+    //lamports a disputer must post to raise a dispute
+    pub dispute_bond: u64,
+    //seconds before reveal_end_time from which sealed votes can be auto revealed
+    pub auto_reveal_window: i64,
+    //distinct node authorities that must have revealed for resolve to succeed
+    pub min_distinct_authorities: u64,
+    //lamports a slasher posts with each slash, forfeited to the node if the slash is overturned
+    pub slash_bond: u64,
+    //slots that must pass after the reveal phase starts before resolve, 0 disables
+    pub min_reveal_slots: u64,
+    //program notified by CPI whenever a node is slashed
+    pub slash_callback_program: Option<Pubkey>,
+    //share of each winner's reward redistributed evenly to non-slashed losing revealers
+    pub loser_rebate_bps: u16,
+    //price condition that lets anyone start the request through try_trigger
+    pub price_trigger: Option<PriceTrigger>,
+    //reputation an authority needs to join, 0 lets anyone join
+    pub min_reputation_to_join: u64,
+    //distinct slashers needed before a slash takes effect; 0 and 1 both slash immediately
+    pub required_witnesses: u8,
+    //independent yes/no questions answered with reveal_mask, 0 for a single question via reveal
+    pub num_bits: u8,
+    //bits a node must match to count as consensus, 0 requires all of them
+    pub min_matching_bits: u8,
+    //only the authority may call resolve
+    pub authority_resolves: bool,
+    //seconds after reveal_end_time the authority has to resolve, 0 for no deadline
+    pub resolve_deadline: i64,
+    //posted by the authority at initialize, refunded on a timely resolve
+    pub resolution_bond: u64,
+    //share of the bond paid to revealed nodes when resolution is late
+    pub late_penalty_bps: u16,
+    //split the reveal window into one slot per node and only accept reveals in a node's own slot
+    pub ordered_reveal: bool,
+    //resolve also refunds losing, non-slashed revealers their collateral in the same pass
+    pub auto_settle: bool,
+    //share of the reward withheld from consensus nodes revealing inside late_reveal_window
+    pub late_reveal_penalty_bps: u16,
+    //seconds before reveal_end_time that count as a late reveal, 0 disables the penalty
+    pub late_reveal_window: i64,
+}
+
+//a version 1 vote record, from before reveals recorded the node's stake
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LegacyVoteRecordV1 {
+    pub node: Pubkey,
+    pub authority: Pubkey,
+    pub vote: bool,
+    pub mask: u64,
+    pub revealed_at: i64,
+}
+
+//the Oracle layout written by version 1 exports
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyOracleV1 {
+    pub authority: Pubkey,
+    pub config: LegacyOracleConfigV1,
+    pub is_resolved: bool,
+    pub resolution_bit: bool,
+    pub resolution_mask: u64,
+    pub phase: Phase,
+    pub reveal_end_time: i64,
+    pub total_nodes: u64,
+    pub committed_nodes: u64,
+    pub created_at: i64,
+    pub round: u64,
+    pub slashed_this_round: u64,
+    pub request_start_time: i64,
+    pub reward_per_node: u64,
+    pub loser_rebate: u64,
+    pub transition_node: Option<Pubkey>,
+    pub resolved_at: i64,
+    pub dispute: Option<Pubkey>,
+    pub finalized_at: Option<i64>,
+    pub reveal_start_slot: u64,
+    pub reveal_started_at: i64,
+    pub reveal_extension: i64,
+    pub paused_duration: i64,
+    pub reveal_seed: [u8; 32],
+    pub revealed_nonces: Vec<u64>,
+    pub votes_snapshot: Vec<LegacyVoteRecordV1>,
+}
+
+//fields added since version 1 take the values initialize gives them; every version 1 node staked
+//exactly the collateral, so that is the stake its vote records carry
+impl From<LegacyOracleV1> for Oracle {
+    fn from(legacy: LegacyOracleV1) -> Self {
+        let c = legacy.config;
+        let collateral = c.collateral;
+        let config = OracleConfig {
+            collateral: c.collateral,
+            reveal_duration: c.reveal_duration,
+            max_nodes: c.max_nodes,
+            min_reveal_seconds_per_node: c.min_reveal_seconds_per_node,
+            abandon_timeout: c.abandon_timeout,
+            true_reveal_stake: c.true_reveal_stake,
+            false_reveal_stake: c.false_reveal_stake,
+            tie_break_policy: c.tie_break_policy,
+            max_slash_per_round: c.max_slash_per_round,
+            resolve_bounty: c.resolve_bounty,
+            bind_start_time: c.bind_start_time,
+            unique_nonces_required: c.unique_nonces_required,
+            no_reveal_default: c.no_reveal_default,
+            rejoin_cooldown: c.rejoin_cooldown,
+            deferred_funding: c.deferred_funding,
+            transition_bonus: c.transition_bonus,
+            dispute_window: c.dispute_window,
+            dispute_bond: c.dispute_bond,
+            auto_reveal_window: c.auto_reveal_window,
+            min_distinct_authorities: c.min_distinct_authorities,
+            slash_bond: c.slash_bond,
+            min_reveal_slots: c.min_reveal_slots,
+            slash_callback_program: c.slash_callback_program,
+            loser_rebate_bps: c.loser_rebate_bps,
+            price_trigger: c.price_trigger,
+            min_reputation_to_join: c.min_reputation_to_join,
+            required_witnesses: c.required_witnesses,
+            num_bits: c.num_bits,
+            min_matching_bits: c.min_matching_bits,
+            authority_resolves: c.authority_resolves,
+            resolve_deadline: c.resolve_deadline,
+            resolution_bond: c.resolution_bond,
+            late_penalty_bps: c.late_penalty_bps,
+            ordered_reveal: c.ordered_reveal,
+            auto_settle: c.auto_settle,
+            late_reveal_penalty_bps: c.late_reveal_penalty_bps,
+            late_reveal_window: c.late_reveal_window,
+            fee_recipient: legacy.authority,
+            // Version 1 resolved whatever the participation, so in-flight rounds keep doing so
+            allow_minority_resolve: true,
+            ..OracleConfig::default()
+        };
+        let votes_snapshot: Vec<VoteRecord> = legacy
+            .votes_snapshot
+            .into_iter()
+            .map(|record| VoteRecord {
+                node: record.node,
+                authority: record.authority,
+                vote: record.vote,
+                mask: record.mask,
+                revealed_at: record.revealed_at,
+                stake: collateral,
+            })
+            .collect();
+        Oracle {
+            authority: legacy.authority,
+            config,
+            question_hash: [0; 32],
+            is_resolved: legacy.is_resolved,
+            resolution_bit: legacy.resolution_bit,
+            resolution_mask: legacy.resolution_mask,
+            resolution_outcome: legacy.resolution_bit as u8,
+            phase: legacy.phase,
+            reveal_end_time: legacy.reveal_end_time,
+            total_nodes: legacy.total_nodes,
+            committed_nodes: legacy.committed_nodes,
+            revealed_nodes: votes_snapshot.len() as u64,
+            tallied_nodes: 0,
+            tally_true_votes: 0,
+            tally_false_votes: 0,
+            tally_true_weight: 0,
+            tally_false_weight: 0,
+            tally_authorities: 0,
+            created_at: legacy.created_at,
+            round: legacy.round,
+            slashed_this_round: legacy.slashed_this_round,
+            request_start_time: legacy.request_start_time,
+            reward_per_node: legacy.reward_per_node,
+            loser_rebate: legacy.loser_rebate,
+            consensus_stake: 0,
+            stake_reward_pool: 0,
+            reward_pool: 0,
+            refund_share: 0,
+            bounty_pool: 0,
+            slashed_pool: 0,
+            mint: Pubkey::default(),
+            vault: Pubkey::default(),
+            commit_end_time: 0,
+            transition_node: legacy.transition_node,
+            resolved_at: legacy.resolved_at,
+            dispute: legacy.dispute,
+            finalized_at: legacy.finalized_at,
+            dispute_end_time: legacy.resolved_at,
+            reveal_start_slot: legacy.reveal_start_slot,
+            reveal_started_at: legacy.reveal_started_at,
+            reveal_extension: legacy.reveal_extension,
+            paused_duration: legacy.paused_duration,
+            paused: false,
+            paused_at: 0,
+            extensions_used: 0,
+            pending_authority: None,
+            request_count: 0,
+            adjudicated_bit: None,
+            slashed_nodes: 0,
+            final_true_votes: 0,
+            final_false_votes: 0,
+            final_slashed: 0,
+            migrated_from: None,
+            reveal_seed: legacy.reveal_seed,
+            revealed_nonces: legacy.revealed_nonces,
+            votes_snapshot,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlashReason {
+    Collusion,
+    NoReveal,
+    WrongReveal,
+}
+
+//instruction data sent to the slash callback program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SlashNotice {
+    pub oracle: Pubkey,
+    pub slashed_node: Pubkey,
+    pub reason: SlashReason,
+}
+
+//soulbound: one per authority, only ever written by record_reputation
+#[account]
+pub struct Reputation {
+    pub authority: Pubkey,
+    pub score: u64,
+}
+
+//one question put to an oracle's node set by create_request, seeded by the oracle and its index
+#[account]
+pub struct Request {
+    pub oracle: Pubkey,
+    pub index: u64,
+    pub question_hash: [u8; 32],
+    //Commit, Reveal, then Complete, Tied or Cancelled
+    pub phase: Phase,
+    //reveal opens once this many, less slashed_nodes, cover every unslashed node of the oracle
+    pub committed_nodes: u64,
+    pub reveal_end_time: i64,
+    pub true_votes: u64,
+    pub false_votes: u64,
+    pub resolution_bit: bool,
+    //nodes slashed on this request, and the collateral seized from them awaiting the winning ballots
+    pub slashed_nodes: u64,
+    pub slashed_pool: u64,
+    //each winning ballot's share of slashed_pool, set at resolve_request
+    pub reward_per_node: u64,
+}
+
+impl Request {
+    pub const LEN: usize = 32 + 8 + 32 + 1 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8;
+}
+
+//a node's commitment and reveal for one request
+#[account]
+pub struct Ballot {
+    pub request: Pubkey,
+    pub node: Pubkey,
+    pub vote_hash: [u8; 32],
+    pub vote: Option<bool>,
+    //share of the request's slashed pool paid out through claim_ballot_reward
+    pub rewarded: bool,
+}
+
+impl Ballot {
+    pub const LEN: usize = 32 + 32 + 32 + 2 + 1;
+}
+
+//track record of one authority across every oracle it has run nodes in, written by record_operator
+#[account]
+pub struct Operator {
+    pub authority: Pubkey,
+    //revealed with the consensus of a completed oracle
+    pub correct_resolutions: u64,
+    pub slashed_count: u64,
+    //revealed votes, whatever the outcome
+    pub total_participations: u64,
+}
+
+impl Operator {
+    pub const LEN: usize = 32 + 8 + 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NodeSummary {
+    pub authority: Pubkey,
+    pub state: NodeState,
+    pub stake: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OracleStatus {
+    pub phase: Phase,
     pub total_nodes: u64,
     pub committed_nodes: u64,
+    pub revealed_nodes: u64,
+    pub reveal_end_time: i64,
+    pub is_resolved: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct NodeVote {
+    //None until the node reveals
+    pub vote: Option<bool>,
+    pub slashed: bool,
+    pub claimed: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(config: OracleConfig)]
+pub struct Initialize<'info> {
+    #[account(init, payer = authority, space = 8 + Oracle::space(&config))]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitTokenVault<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: PDA that owns the vault; it holds no data and only signs transfers out of the vault
+    #[account(seeds = [b"vault", oracle.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinNetwork<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = node_authority,
+        space = 8 + Node::LEN,
+        seeds = [b"node", oracle.key().as_ref(), node_authority.key().as_ref()],
+        bump
+    )]
+    pub node: Account<'info, Node>,
+    #[account(
+        init_if_needed,
+        payer = node_authority,
+        space = 8 + 32 + 32 + 9,
+        seeds = [b"membership", oracle.key().as_ref(), node_authority.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    #[account(mut)]
+    pub node_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundJoin<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub node: Account<'info, Node>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveNetwork<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = authority, has_one = oracle, close = authority)]
+    pub node: Account<'info, Node>,
+    #[account(
+        mut,
+        seeds = [b"membership", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpStake<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = authority, has_one = oracle)]
+    pub node: Account<'info, Node>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StartRequest<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRequest<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCommit<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct RecomputeDeadline<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendReveal<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TryTrigger<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    /// CHECK: must be the oracle's configured price account, checked in try_trigger
+    pub price_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Commit<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub node: Account<'info, Node>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeclareAbstain<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = oracle @ ErrorCode::NodeOracleMismatch,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub node: Account<'info, Node>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Reveal<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub node: Account<'info, Node>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitSealedVote<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = authority, has_one = oracle)]
+    pub node: Account<'info, Node>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AutoReveal<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub node: Account<'info, Node>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SlashColluding<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle @ ErrorCode::NodeOracleMismatch)]
+    pub colluding_node: Account<'info, Node>,
+    #[account(mut)]
+    pub slasher: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-#[account]
-pub struct Node {
-    pub authority: Pubkey,
-    pub vote_hash: Option<[u8; 32]>,
-    pub vote: Option<bool>,
-    pub slashed: bool,
+#[derive(Accounts)]
+pub struct SlashNoReveal<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = authority, space = 8 + 32 + 8 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8)]
+pub struct SlashWrongReveal<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OverturnSlash<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub node: Account<'info, Node>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Resolve<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    /// CHECK: receives the protocol fee; must be the configured fee recipient
+    #[account(mut, address = oracle.config.fee_recipient @ ErrorCode::InvalidFeeRecipient)]
+    pub fee_recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TallyVotes<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct PenalizeLateResolution<'info> {
+    #[account(mut, has_one = authority)]
     pub oracle: Account<'info, Oracle>,
+    /// CHECK: receives the unpenalized part of the bond; bound to the oracle by has_one
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    /// CHECK: receives the protocol fee; must be the configured fee recipient
+    #[account(mut, address = oracle.config.fee_recipient @ ErrorCode::InvalidFeeRecipient)]
+    pub fee_recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateCollateral<'info> {
+    #[account(mut)]
+    pub source_oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = authority)]
+    pub source_node: Account<'info, Node>,
+    #[account(mut)]
+    pub destination_oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Node::LEN,
+        seeds = [b"node", destination_oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub destination_node: Account<'info, Node>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 9,
+        seeds = [b"membership", destination_oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct JoinNetwork<'info> {
+pub struct BatchReveal<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAbandoned<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMissedReward<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle, has_one = authority)]
+    pub node: Account<'info, Node>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimCollateral<'info> {
     #[account(mut)]
     pub oracle: Account<'info, Oracle>,
-    #[account(init, payer = node_authority, space = 8 + 32 + 33 + 2 + 1)]
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
     pub node: Account<'info, Node>,
     #[account(mut)]
-    pub node_authority: Signer<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AuthorityDrain<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseNode<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = authority, has_one = oracle, close = authority)]
+    pub node: Account<'info, Node>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordReputation<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub node: Account<'info, Node>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + 32 + 8,
+        seeds = [b"reputation", node.authority.as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct StartRequest<'info> {
+pub struct RecordOperator<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub node: Account<'info, Node>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Operator::LEN,
+        seeds = [b"operator", node.authority.as_ref()],
+        bump
+    )]
+    pub operator: Account<'info, Operator>,
     #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EstimateReward<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(has_one = oracle @ ErrorCode::NodeOracleMismatch)]
+    pub node: Account<'info, Node>,
+}
+
+#[derive(Accounts)]
+pub struct Roster<'info> {
+    pub oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct ReadConfig<'info> {
+    pub oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct ReadNode<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(has_one = oracle @ ErrorCode::NodeOracleMismatch)]
+    pub node: Account<'info, Node>,
+}
+
+#[derive(Accounts)]
+pub struct ExportState<'info> {
+    #[account(mut, has_one = authority, close = export)]
     pub oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 1 + 32 + 32 + 8 + 8 + 4 + Oracle::space(&oracle.config),
+        seeds = [b"export", oracle.key().as_ref()],
+        bump
+    )]
+    pub export: Account<'info, StateExport>,
+    #[account(mut)]
     pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Commit<'info> {
+pub struct ImportState<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        close = authority,
+        seeds = [b"export", export.oracle.as_ref()],
+        bump
+    )]
+    pub export: Account<'info, StateExport>,
+    #[account(
+        init,
+        payer = authority,
+        space = export.import_space(),
+        seeds = [b"oracle", export.oracle.as_ref()],
+        bump
+    )]
+    pub oracle: Account<'info, Oracle>,
     #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ImportNode<'info> {
+    #[account(mut, constraint = oracle.migrated_from.is_some() @ ErrorCode::NodeOracleMismatch)]
     pub oracle: Account<'info, Oracle>,
-    #[account(mut, has_one = authority)]
+    #[account(
+        mut,
+        has_one = authority,
+        close = authority,
+        constraint = oracle.migrated_from == Some(source_node.oracle) @ ErrorCode::NodeOracleMismatch,
+        seeds = [b"node", source_node.oracle.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub source_node: Account<'info, Node>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Node::LEN,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
     pub node: Account<'info, Node>,
+    #[account(mut)]
     pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Reveal<'info> {
+pub struct ProduceAttestation<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 1 + 8 + 32 + 8,
+        seeds = [b"attestation", oracle.key().as_ref(), &oracle.round.to_le_bytes()],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RaiseDispute<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Finalize<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct SettleDispute<'info> {
+    #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: refunded the dispute bond if upheld; must match oracle.dispute
+    #[account(mut)]
+    pub disputer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
     #[account(mut)]
     pub oracle: Account<'info, Oracle>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRequest<'info> {
     #[account(mut, has_one = authority)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Request::LEN,
+        seeds = [b"request", oracle.key().as_ref(), &oracle.request_count.to_le_bytes()],
+        bump
+    )]
+    pub request: Account<'info, Request>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitRequest<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub request: Account<'info, Request>,
+    #[account(
+        has_one = authority,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
     pub node: Account<'info, Node>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Ballot::LEN,
+        seeds = [b"ballot", request.key().as_ref(), node.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, Ballot>,
+    #[account(mut)]
     pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SlashColluding<'info> {
+pub struct RevealRequest<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub request: Account<'info, Request>,
+    #[account(
+        has_one = authority,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub node: Account<'info, Node>,
+    #[account(
+        mut,
+        has_one = request,
+        has_one = node,
+        seeds = [b"ballot", request.key().as_ref(), node.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, Ballot>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRequestCommit<'info> {
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub request: Account<'info, Request>,
+}
+
+#[derive(Accounts)]
+pub struct SlashRequestColluding<'info> {
     #[account(mut)]
     pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub request: Account<'info, Request>,
+    #[account(mut, has_one = oracle @ ErrorCode::NodeOracleMismatch)]
+    pub node: Account<'info, Node>,
+    #[account(
+        has_one = request,
+        has_one = node,
+        seeds = [b"ballot", request.key().as_ref(), node.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, Ballot>,
     #[account(mut)]
-    pub colluding_node: Account<'info, Node>,
     pub slasher: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct Resolve<'info> {
+pub struct SlashRequestNoReveal<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub request: Account<'info, Request>,
+    #[account(mut, has_one = oracle @ ErrorCode::NodeOracleMismatch)]
+    pub node: Account<'info, Node>,
+    #[account(
+        has_one = request,
+        has_one = node,
+        seeds = [b"ballot", request.key().as_ref(), node.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, Ballot>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveRequest<'info> {
+    #[account(mut)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub request: Account<'info, Request>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBallotReward<'info> {
     #[account(mut)]
     pub oracle: Account<'info, Oracle>,
+    #[account(mut, has_one = oracle)]
+    pub request: Account<'info, Request>,
+    #[account(
+        has_one = authority,
+        seeds = [b"node", oracle.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub node: Account<'info, Node>,
+    #[account(
+        mut,
+        has_one = request,
+        has_one = node,
+        seeds = [b"ballot", request.key().as_ref(), node.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, Ballot>,
+    #[account(mut)]
     pub authority: Signer<'info>,
 }
 
+#[event]
+pub struct OracleInitialized {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub authority: Pubkey,
+    pub question_hash: [u8; 32],
+}
+
+#[event]
+pub struct NodeJoined {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub node: Pubkey,
+    pub authority: Pubkey,
+    pub stake: u64,
+}
+
+#[event]
+pub struct RequestStarted {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub round: u64,
+    pub request_start_time: i64,
+    //committee size when the commit phase opened
+    pub total_nodes: u64,
+}
+
+#[event]
+pub struct VoteCommitted {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub node: Pubkey,
+}
+
+#[event]
+pub struct VoteRevealed {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub node: Pubkey,
+    pub vote: bool,
+    pub mask: u64,
+}
+
+#[event]
+pub struct OracleResolved {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub resolution_bit: bool,
+    pub true_votes: u64,
+    pub false_votes: u64,
+}
+
 #[event]
 pub struct NodeSlashed {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub slashed_node: Pubkey,
+}
+
+#[event]
+pub struct SlashCallbackFailed {
+    pub event_version: u8,
     pub oracle: Pubkey,
     pub slashed_node: Pubkey,
+    pub callback_program: Pubkey,
+}
+
+#[event]
+pub struct ResolutionFinalized {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub resolution_bit: bool,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct OracleDrained {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RequestCreated {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub request: Pubkey,
+    pub index: u64,
+    pub question_hash: [u8; 32],
+}
+
+#[event]
+pub struct RequestResolved {
+    pub event_version: u8,
+    pub oracle: Pubkey,
+    pub request: Pubkey,
+    pub index: u64,
+    pub resolution_bit: bool,
+    pub true_votes: u64,
+    pub false_votes: u64,
 }
 
 #[error_code]
@@ -309,4 +4613,154 @@ pub enum ErrorCode {
     MaxNodesReached,
     #[msg("Unauthorized access")]
     UnauthorizedAccess,
+    #[msg("Node has already been slashed")]
+    AlreadySlashed,
+    #[msg("Distribution would leave the oracle below its rent-exempt minimum")]
+    WouldBreakRentExemption,
+    #[msg("Reveal window is too short for the maximum number of nodes")]
+    RevealWindowTooShort,
+    #[msg("Node does not belong to this oracle")]
+    NodeOracleMismatch,
+    #[msg("Node did not vote with consensus")]
+    NotConsensusNode,
+    #[msg("Node collateral has already been claimed")]
+    AlreadyClaimed,
+    #[msg("Insufficient collateral")]
+    InsufficientCollateral,
+    #[msg("Abandon timeout has not been reached")]
+    AbandonTimeoutNotReached,
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+    #[msg("Node stake is below the requirement for this vote")]
+    InsufficientStake,
+    #[msg("Slashing cap for this round has been reached")]
+    SlashCapReached,
+    #[msg("Invalid node state transition")]
+    InvalidNodeState,
+    #[msg("Too many nodes requested for the roster")]
+    RosterTooLarge,
+    #[msg("Nonce was already revealed by another node")]
+    DuplicateNonce,
+    #[msg("Rejoin cooldown is still active")]
+    RejoinCooldownActive,
+    #[msg("Reveal deadline overflows")]
+    InvalidDeadline,
+    #[msg("More nodes committed than joined")]
+    CommitCountInvariantViolated,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("A dispute is pending")]
+    DisputePending,
+    #[msg("Resolution already finalized")]
+    AlreadyFinalized,
+    #[msg("Auto reveal window has not opened")]
+    AutoRevealNotOpen,
+    #[msg("Node has no sealed vote")]
+    NoSealedVote,
+    #[msg("Escrow key does not match the sealed vote")]
+    InvalidEscrowKey,
+    #[msg("Not enough distinct authorities revealed")]
+    InsufficientDistinctVoters,
+    #[msg("Not enough slots have passed since the reveal phase started")]
+    RevealSlotsNotElapsed,
+    #[msg("Basis points must not exceed 10000")]
+    InvalidBasisPoints,
+    #[msg("Oracle has no price trigger")]
+    NoPriceTrigger,
+    #[msg("Price account does not match the oracle's trigger")]
+    PriceAccountMismatch,
+    #[msg("Price account is not a trading Pyth price feed")]
+    InvalidPriceAccount,
+    #[msg("Price has not crossed the trigger threshold")]
+    PriceTriggerNotMet,
+    #[msg("Authority's reputation is below the oracle's minimum")]
+    InsufficientReputation,
+    #[msg("Reputation account does not belong to the authority")]
+    InvalidReputationAccount,
+    #[msg("Slasher already witnessed this node")]
+    DuplicateWitness,
+    #[msg("Required witnesses exceeds the maximum")]
+    TooManyWitnesses,
+    #[msg("Reveal instruction does not match the oracle's question mode")]
+    WrongRevealMode,
+    #[msg("Invalid num_bits or min_matching_bits")]
+    InvalidBitCount,
+    #[msg("Resolve deadline has passed")]
+    ResolveDeadlinePassed,
+    #[msg("Resolve deadline has not been reached")]
+    ResolveDeadlineNotReached,
+    #[msg("Oracle has no authority resolve deadline")]
+    NoResolveDeadline,
+    #[msg("Reveal is outside the node's reveal slot")]
+    RevealOutOfSlot,
+    #[msg("Exported state was written by an unsupported layout version")]
+    UnsupportedExportVersion,
+    #[msg("Exported state could not be encoded or decoded")]
+    InvalidExportState,
+    #[msg("Resolve must be passed every node in the committee")]
+    IncompleteNodeSet,
+    #[msg("Collateral mode can only be changed before any node joins")]
+    CollateralModeLocked,
+    #[msg("This instruction only supports native lamport collateral")]
+    NativeCollateralOnly,
+    #[msg("Token collateral accounts are missing or do not match the oracle's vault")]
+    InvalidTokenAccounts,
+    #[msg("The commit deadline has not passed")]
+    CommitDeadlineNotReached,
+    #[msg("The commit phase has not closed yet")]
+    CommitPhaseNotClosed,
+    #[msg("Node account is not owned by this program")]
+    ForeignNodeAccount,
+    #[msg("Outcome count must be 0, 2 or more, and cannot be combined with num_bits")]
+    InvalidOutcomeCount,
+    #[msg("Stake weighting only applies to single-question binary votes")]
+    StakeWeightedBinaryOnly,
+    #[msg("Fewer than half of the nodes revealed")]
+    InsufficientParticipation,
+    #[msg("Not enough nodes have joined to start a request")]
+    NotEnoughNodes,
+    #[msg("The reveal deadline has already been extended the maximum number of times")]
+    MaxExtensionsReached,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Account is not the pending disputer")]
+    NotDisputer,
+    #[msg("The oracle is paused")]
+    Paused,
+    #[msg("Node still has collateral or a reward to claim")]
+    UnclaimedFunds,
+    #[msg("Question hash must not be empty")]
+    EmptyQuestionHash,
+    #[msg("Account is not the oracle's fee recipient")]
+    InvalidFeeRecipient,
+    #[msg("Every revealed vote has already been tallied")]
+    AllVotesTallied,
+    #[msg("Not every revealed vote has been tallied")]
+    TallyIncomplete,
+    #[msg("The reveal phase has no deadline; it was not started")]
+    RevealNotStarted,
+    #[msg("The oracle has already been resolved")]
+    AlreadyResolved,
+    #[msg("Collateral must be greater than zero")]
+    InvalidCollateral,
+    #[msg("The oracle must allow at least one node")]
+    InvalidMaxNodes,
+    #[msg("Reveal duration must be positive and at most MAX_REVEAL_DURATION")]
+    InvalidRevealDuration,
+    #[msg("The oracle was not created in adjudicated mode")]
+    NotAdjudicated,
+    #[msg("A different true bit was already adjudicated")]
+    AdjudicationMismatch,
+    #[msg("The emergency timeout after the reveal deadline has not passed")]
+    EmergencyTimeoutNotReached,
+    #[msg("Node abstained from this round and has no vote to reveal")]
+    NodeAbstained,
+    #[msg("Dispute resolution is not a valid outcome for this oracle")]
+    InvalidDisputeResolution,
+    #[msg("min_nodes must not exceed max_nodes")]
+    InvalidMinNodes,
+    #[msg("Reveal extensions would exceed MAX_REVEAL_EXTENSION")]
+    RevealExtensionCapReached,
 }
\ No newline at end of file
@@ -2,8 +2,483 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
 use anchor_lang::solana_program::system_program;
 use binary_oracle::*;
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::associated_token::{get_associated_token_address, spl_associated_token_account};
+use anchor_spl::token::{spl_token, TokenAccount};
+use base64::Engine;
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+struct TestEnv {
+    banks_client: BanksClient,
+    payer: Keypair,
+    program_id: Pubkey,
+    sent: u64,
+}
+
+impl TestEnv {
+    async fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let program_test = ProgramTest::new(
+            "binary_oracle",
+            program_id,
+            processor!(binary_oracle::entry),
+        );
+        Self::start(program_test, program_id).await
+    }
+
+    // Also loads the slash recorder at `callback_program`
+    async fn with_slash_recorder(callback_program: Pubkey) -> Self {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "binary_oracle",
+            program_id,
+            processor!(binary_oracle::entry),
+        );
+        program_test.add_program("slash_recorder", callback_program, processor!(record_slash));
+        Self::start(program_test, program_id).await
+    }
+
+    // Also preloads the given accounts, e.g. mocked price feeds
+    async fn with_accounts(accounts: Vec<(Pubkey, solana_sdk::account::Account)>) -> Self {
+        Self::with_program_accounts(Pubkey::new_unique(), accounts).await
+    }
+
+    // Like `with_accounts`, but under a fixed program id so preloaded program accounts stay valid
+    async fn with_program_accounts(program_id: Pubkey, accounts: Vec<(Pubkey, solana_sdk::account::Account)>) -> Self {
+        let mut program_test = ProgramTest::new(
+            "binary_oracle",
+            program_id,
+            processor!(binary_oracle::entry),
+        );
+        for (key, account) in accounts {
+            program_test.add_account(key, account);
+        }
+        Self::start(program_test, program_id).await
+    }
+
+    // Preloads `export` at its PDA, as a program that wrote it would have left it, with its authority funded
+    async fn with_export(program_id: Pubkey, export: &StateExport) -> (Self, Pubkey) {
+        let address = Pubkey::find_program_address(&[b"export", export.oracle.as_ref()], &program_id).0;
+        let mut data = Vec::new();
+        export.try_serialize(&mut data).unwrap();
+        let account = |lamports, data, owner| solana_sdk::account::Account {
+            lamports,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let accounts = vec![
+            (address, account(10_000_000_000 + export.funds, data, program_id)),
+            (export.authority, account(10_000_000_000, Vec::new(), system_program::ID)),
+        ];
+        (Self::with_program_accounts(program_id, accounts).await, address)
+    }
+
+    async fn start(program_test: ProgramTest, program_id: Pubkey) -> Self {
+        let (banks_client, payer, _) = program_test.start().await;
+        TestEnv { banks_client, payer, program_id, sent: 0 }
+    }
+
+    async fn send(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let recent_blockhash: Hash = self.banks_client.get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        // Resending an identical transaction, e.g. retrying after a failure, would be rejected
+        // as already processed, so give each one a distinct priority fee
+        self.sent += 1;
+        let mut ixs = ixs.to_vec();
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(self.sent));
+        let mut transaction = Transaction::new_with_payer(&ixs, Some(&self.payer.pubkey()));
+        transaction.sign(&all_signers, recent_blockhash);
+        self.banks_client.process_transaction(transaction).await
+    }
+
+    // Like `send`, but reports the compute units the transaction consumed
+    async fn send_metered(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> u64 {
+        let recent_blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let mut transaction = Transaction::new_with_payer(ixs, Some(&self.payer.pubkey()));
+        transaction.sign(&all_signers, recent_blockhash);
+        let result = self
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        result.result.unwrap();
+        result.metadata.unwrap().compute_units_consumed
+    }
+
+    // Sends the transaction and returns its program logs
+    async fn send_with_logs(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Vec<String> {
+        let recent_blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let mut transaction = Transaction::new_with_payer(ixs, Some(&self.payer.pubkey()));
+        transaction.sign(&all_signers, recent_blockhash);
+        let result = self
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        result.result.unwrap();
+        result.metadata.unwrap().log_messages
+    }
+
+    // Sends the transaction and decodes every event of type T from its logs
+    async fn send_with_events<T: AnchorDeserialize + Discriminator>(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Vec<T> {
+        self.send_with_logs(ixs, signers)
+            .await
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .filter(|data| data.starts_with(&T::DISCRIMINATOR))
+            .map(|data| T::try_from_slice(&data[8..]).unwrap())
+            .collect()
+    }
+
+    // Runs the instruction without committing it and decodes its return data
+    async fn simulate<T: AnchorDeserialize>(&mut self, ix: Instruction, signers: &[&Keypair]) -> T {
+        let recent_blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let mut transaction = Transaction::new_with_payer(&[ix], Some(&self.payer.pubkey()));
+        transaction.sign(&all_signers, recent_blockhash);
+        let simulation = self.banks_client.simulate_transaction(transaction).await.unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        T::try_from_slice(&return_data.data).unwrap()
+    }
+
+    async fn set_time(&mut self, unix_timestamp: i64) {
+        self.set_clock(100, unix_timestamp).await;
+    }
+
+    async fn set_clock(&mut self, slot: u64, unix_timestamp: i64) {
+        self.banks_client.set_sysvar(&Clock {
+            slot,
+            epoch_start_timestamp: 0,
+            epoch: 0,
+            leader_schedule_epoch: 0,
+            unix_timestamp,
+        });
+    }
+
+    async fn lamports(&mut self, key: Pubkey) -> u64 {
+        self.banks_client.get_account(key).await.unwrap().unwrap().lamports
+    }
+
+    async fn oracle(&mut self, key: Pubkey) -> Oracle {
+        let account = self.banks_client.get_account(key).await.unwrap().unwrap();
+        Oracle::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn node(&mut self, key: Pubkey) -> Node {
+        let account = self.banks_client.get_account(key).await.unwrap().unwrap();
+        Node::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn funded_keypair(&mut self, lamports: u64) -> Keypair {
+        let keypair = Keypair::new();
+        let ix = system_instruction::transfer(&self.payer.pubkey(), &keypair.pubkey(), lamports);
+        self.send(&[ix], &[]).await.unwrap();
+        keypair
+    }
+
+    async fn initialize(&mut self, authority: &Keypair, collateral: u64, reveal_duration: i64, max_nodes: u64) -> Keypair {
+        self.initialize_with(authority, config(collateral, reveal_duration, max_nodes))
+            .await
+            .unwrap()
+    }
+
+    async fn initialize_with(&mut self, authority: &Keypair, config: OracleConfig) -> Result<Keypair, BanksClientError> {
+        self.initialize_question(authority, config, question_hash(QUESTION)).await
+    }
+
+    async fn initialize_question(
+        &mut self,
+        authority: &Keypair,
+        config: OracleConfig,
+        question_hash: [u8; 32],
+    ) -> Result<Keypair, BanksClientError> {
+        let oracle = Keypair::new();
+        let ix = binary_oracle::instruction::initialize(
+            self.program_id,
+            authority.pubkey(),
+            oracle.pubkey(),
+            config,
+            question_hash,
+        );
+        self.send(&[ix], &[&oracle, authority]).await?;
+        Ok(oracle)
+    }
+
+    // Joins a fresh authority to the oracle, returning its (node account, authority keypair)
+    async fn join(&mut self, oracle: Pubkey) -> (Pubkey, Keypair) {
+        let authority = self.funded_keypair(10_000_000_000).await;
+        self.try_join(oracle, &authority).await.unwrap();
+        (node_address(&self.program_id, &oracle, &authority.pubkey()), authority)
+    }
+
+    // Joins posting exactly the oracle's collateral
+    async fn try_join(&mut self, oracle: Pubkey, authority: &Keypair) -> Result<(), BanksClientError> {
+        let stake = self.oracle(oracle).await.config.collateral;
+        self.try_join_with_stake(oracle, authority, stake).await
+    }
+
+    async fn try_join_with_stake(&mut self, oracle: Pubkey, authority: &Keypair, stake: u64) -> Result<(), BanksClientError> {
+        let ix = binary_oracle::instruction::join_network(
+            self.program_id,
+            oracle,
+            node_address(&self.program_id, &oracle, &authority.pubkey()),
+            membership_address(&self.program_id, &oracle, &authority.pubkey()),
+            authority.pubkey(),
+            stake,
+        );
+        self.send(&[ix], &[authority]).await
+    }
+
+    // Moves the node `authority` runs on an exported oracle over to `oracle`, its imported successor
+    async fn import_node(&mut self, oracle: Pubkey, source_node: &Pubkey, authority: &Keypair) -> Result<Pubkey, BanksClientError> {
+        let node = node_address(&self.program_id, &oracle, &authority.pubkey());
+        let ix = binary_oracle::instruction::import_node(self.program_id, oracle, *source_node, node, authority.pubkey());
+        self.send(&[ix], &[authority]).await?;
+        Ok(node)
+    }
+
+    // Opens the oracle's next request and returns its address
+    async fn create_request(&mut self, oracle: Pubkey, authority: &Keypair) -> Pubkey {
+        let index = self.oracle(oracle).await.request_count;
+        let request = request_address(&self.program_id, &oracle, index);
+        let ix = binary_oracle::instruction::create_request(
+            self.program_id,
+            oracle,
+            request,
+            authority.pubkey(),
+            question_hash(&format!("{QUESTION} #{index}")),
+        );
+        self.send(&[ix], &[authority]).await.unwrap();
+        request
+    }
+
+    async fn commit_request(&mut self, oracle: Pubkey, request: Pubkey, node: &Pubkey, authority: &Keypair, vote: bool, nonce: [u8; 32]) {
+        let ix = binary_oracle::instruction::commit_request(
+            self.program_id,
+            oracle,
+            request,
+            *node,
+            ballot_address(&self.program_id, &request, node),
+            authority.pubkey(),
+            compute_vote_hash(&request, node, vote, &nonce, None),
+        );
+        self.send(&[ix], &[authority]).await.unwrap();
+    }
+
+    async fn reveal_request(&mut self, oracle: Pubkey, request: Pubkey, node: &Pubkey, authority: &Keypair, vote: bool, nonce: [u8; 32]) {
+        let ix = binary_oracle::instruction::reveal_request(
+            self.program_id,
+            oracle,
+            request,
+            *node,
+            ballot_address(&self.program_id, &request, node),
+            authority.pubkey(),
+            vote,
+            nonce,
+        );
+        self.send(&[ix], &[authority]).await.unwrap();
+    }
+
+    async fn request(&mut self, key: Pubkey) -> Request {
+        let account = self.banks_client.get_account(key).await.unwrap().unwrap();
+        Request::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn start_request(&mut self, oracle: Pubkey, authority: &Keypair) {
+        let ix = binary_oracle::instruction::start_request(self.program_id, oracle, authority.pubkey());
+        self.send(&[ix], &[authority]).await.unwrap();
+    }
+
+    async fn commit(&mut self, oracle: Pubkey, node: &Pubkey, authority: &Keypair, vote: bool, nonce: [u8; 32]) {
+        self.try_commit(oracle, node, authority, vote, nonce).await.unwrap();
+    }
+
+    async fn try_commit(&mut self, oracle: Pubkey, node: &Pubkey, authority: &Keypair, vote: bool, nonce: [u8; 32]) -> Result<(), BanksClientError> {
+        let ix = binary_oracle::instruction::commit(
+            self.program_id,
+            oracle,
+            *node,
+            authority.pubkey(),
+            vote_hash(&oracle, node, vote, &nonce),
+        );
+        self.send(&[ix], &[authority]).await
+    }
+
+    async fn reveal(&mut self, oracle: Pubkey, node: &Pubkey, authority: &Keypair, vote: bool, nonce: [u8; 32]) -> Result<(), BanksClientError> {
+        let ix = binary_oracle::instruction::reveal(
+            self.program_id,
+            oracle,
+            *node,
+            authority.pubkey(),
+            vote,
+            nonce,
+        );
+        self.send(&[ix], &[authority]).await
+    }
+
+    async fn slash(&mut self, oracle: Pubkey, node: Pubkey, slasher: &Keypair, vote: bool, nonce: [u8; 32]) -> Result<(), BanksClientError> {
+        let ix = binary_oracle::instruction::slash_colluding(
+            self.program_id,
+            oracle,
+            node,
+            slasher.pubkey(),
+            vote,
+            nonce,
+        );
+        self.send(&[ix], &[slasher]).await
+    }
+
+    async fn resolve(&mut self, oracle: Pubkey, caller: &Keypair, nodes: &[Pubkey]) -> Result<(), BanksClientError> {
+        let fee_recipient = self.oracle(oracle).await.config.fee_recipient;
+        let ix = with_remaining_accounts(
+            binary_oracle::instruction::resolve(self.program_id, oracle, caller.pubkey(), fee_recipient),
+            nodes,
+        );
+        self.send(&[ix], &[caller]).await
+    }
+}
+
+// Stand-in risk monitor for slash callbacks; logs each slash notice it receives
+fn record_slash(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let notice = SlashNotice::try_from_slice(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    assert_eq!(accounts[1].key, &notice.slashed_node);
+    msg!("recorded slash of {} for {:?}", notice.slashed_node, notice.reason);
+    Ok(())
+}
+
+// Minimal Pyth v2 price account with a trading aggregate price
+fn mock_price_account(price: i64) -> solana_sdk::account::Account {
+    let mut data = vec![0u8; 240];
+    data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[224..228].copy_from_slice(&1u32.to_le_bytes());
+    solana_sdk::account::Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn config(collateral: u64, reveal_duration: i64, max_nodes: u64) -> OracleConfig {
+    OracleConfig {
+        collateral,
+        reveal_duration,
+        max_nodes,
+        ..Default::default()
+    }
+}
+
+const QUESTION: &str = "Will the proposal pass by the end of the voting period?";
+
+fn question_hash(question: &str) -> [u8; 32] {
+    hash(question.as_bytes()).to_bytes()
+}
+
+fn membership_address(program_id: &Pubkey, oracle: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"membership", oracle.as_ref(), authority.as_ref()], program_id).0
+}
+
+fn node_address(program_id: &Pubkey, oracle: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"node", oracle.as_ref(), authority.as_ref()], program_id).0
+}
+
+fn imported_address(program_id: &Pubkey, exported: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"oracle", exported.as_ref()], program_id).0
+}
+
+fn request_address(program_id: &Pubkey, oracle: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"request", oracle.as_ref(), &index.to_le_bytes()], program_id).0
+}
+
+fn ballot_address(program_id: &Pubkey, request: &Pubkey, node: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"ballot", request.as_ref(), node.as_ref()], program_id).0
+}
+
+fn operator_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"operator", authority.as_ref()], program_id).0
+}
+
+fn reputation_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"reputation", authority.as_ref()], program_id).0
+}
+
+fn vote_hash(oracle: &Pubkey, node: &Pubkey, vote: bool, nonce: &[u8; 32]) -> [u8; 32] {
+    outcome_hash(oracle, node, vote as u8, nonce)
+}
+
+fn bound_vote_hash(oracle: &Pubkey, node: &Pubkey, vote: bool, nonce: &[u8; 32], request_start_time: i64) -> [u8; 32] {
+    hash(
+        &[
+            COMMITMENT_DOMAIN,
+            oracle.as_ref(),
+            node.as_ref(),
+            &[vote as u8],
+            &nonce[..],
+            &request_start_time.to_le_bytes(),
+        ]
+        .concat(),
+    )
+    .to_bytes()
+}
+
+fn outcome_hash(oracle: &Pubkey, node: &Pubkey, outcome: u8, nonce: &[u8; 32]) -> [u8; 32] {
+    hash(&[COMMITMENT_DOMAIN, oracle.as_ref(), node.as_ref(), &[outcome], &nonce[..]].concat()).to_bytes()
+}
+
+fn mask_hash(oracle: &Pubkey, node: &Pubkey, mask: u64, nonce: &[u8; 32]) -> [u8; 32] {
+    hash(&[COMMITMENT_DOMAIN, oracle.as_ref(), node.as_ref(), &mask.to_le_bytes(), &nonce[..]].concat()).to_bytes()
+}
+
+fn with_remaining_accounts(mut ix: Instruction, accounts: &[Pubkey]) -> Instruction {
+    ix.accounts.extend(accounts.iter().map(|key| AccountMeta::new(*key, false)));
+    ix
+}
+
+fn with_readonly_accounts(mut ix: Instruction, accounts: &[Pubkey]) -> Instruction {
+    ix.accounts.extend(accounts.iter().map(|key| AccountMeta::new_readonly(*key, false)));
+    ix
+}
+
+// Token collateral accounts in the order token_accounts expects: owner ATA, vault, vault PDA, token program
+fn with_token_accounts(mut ix: Instruction, program_id: &Pubkey, oracle: &Pubkey, owner_token: Pubkey, vault: Pubkey) -> Instruction {
+    let vault_authority = Pubkey::find_program_address(&[b"vault", oracle.as_ref()], program_id).0;
+    ix.accounts.extend([
+        AccountMeta::new(owner_token, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(vault_authority, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+    ix
+}
+
+fn assert_error(result: Result<(), BanksClientError>, error: binary_oracle::ErrorCode) {
+    let err = result.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(u32::from(error)))
+    );
+}
 
 #[tokio::test]
 async fn test_binary_oracle() {
@@ -25,6 +500,8 @@ async fn test_binary_oracle() {
 
     // Initialize the oracle
     let oracle = Keypair::new();
+    let node1_account_key = node_address(&program_id, &oracle.pubkey(), &node1.pubkey());
+    let node2_account_key = node_address(&program_id, &oracle.pubkey(), &node2.pubkey());
     let collateral = 1_000_000; // 1 SOL
     let reveal_duration = 3600; // 1 hour
     let max_nodes = 3;
@@ -36,9 +513,8 @@ async fn test_binary_oracle() {
         program_id,
         oracle_authority.pubkey(),
         oracle.pubkey(),
-        collateral,
-        reveal_duration,
-        max_nodes,
+        config(collateral, reveal_duration, max_nodes),
+        question_hash(QUESTION),
     );
 
     let mut transaction = Transaction::new_with_payer(
@@ -52,8 +528,10 @@ async fn test_binary_oracle() {
     let join_network_ix = binary_oracle::instruction::join_network(
         program_id,
         oracle.pubkey(),
+        node1_account_key,
+        membership_address(&program_id, &oracle.pubkey(), &node1.pubkey()),
         node1.pubkey(),
-        node1.pubkey(),
+        collateral,
     );
 
     let mut transaction = Transaction::new_with_payer(
@@ -64,7 +542,7 @@ async fn test_binary_oracle() {
     banks_client.process_transaction(transaction).await.unwrap();
 
     // Verify node1 has joined and posted collateral
-    let node1_account = banks_client.get_account(node1.pubkey()).await.unwrap().unwrap();
+    let node1_account = banks_client.get_account(node1_account_key).await.unwrap().unwrap();
     assert_eq!(node1_account.lamports, oracle_account_rent);
 
     // Test 2: Nodes committing must have joined and posted collateral
@@ -84,14 +562,14 @@ async fn test_binary_oracle() {
     // Node1 commits
     let vote = true;
     let nonce = [1u8; 32];
-    let vote_hash = hash(&[&[vote as u8], &nonce[..]].concat()).to_bytes();
+    let node1_vote_hash = vote_hash(&oracle.pubkey(), &node1_account_key, vote, &nonce);
 
     let commit_ix = binary_oracle::instruction::commit(
         program_id,
         oracle.pubkey(),
+        node1_account_key,
         node1.pubkey(),
-        node1.pubkey(),
-        vote_hash,
+        node1_vote_hash,
     );
 
     let mut transaction = Transaction::new_with_payer(
@@ -105,16 +583,18 @@ async fn test_binary_oracle() {
     let node2_join_ix = binary_oracle::instruction::join_network(
         program_id,
         oracle.pubkey(),
+        node2_account_key,
+        membership_address(&program_id, &oracle.pubkey(), &node2.pubkey()),
         node2.pubkey(),
-        node2.pubkey(),
+        collateral,
     );
 
     let node2_commit_ix = binary_oracle::instruction::commit(
         program_id,
         oracle.pubkey(),
+        node2_account_key,
         node2.pubkey(),
-        node2.pubkey(),
-        vote_hash,
+        vote_hash(&oracle.pubkey(), &node2_account_key, vote, &nonce),
     );
 
     let mut transaction = Transaction::new_with_payer(
@@ -127,12 +607,12 @@ async fn test_binary_oracle() {
     // Node3 tries to slash Node2 with incorrect hash
     let incorrect_vote = false;
     let incorrect_nonce = [2u8; 32];
-    let incorrect_vote_hash = hash(&[&[incorrect_vote as u8], &incorrect_nonce[..]].concat()).to_bytes();
+    let incorrect_vote_hash = vote_hash(&oracle.pubkey(), &node2_account_key, incorrect_vote, &incorrect_nonce);
 
     let slash_ix = binary_oracle::instruction::slash_colluding(
         program_id,
         oracle.pubkey(),
-        node2.pubkey(),
+        node2_account_key,
         node3.pubkey(),
         incorrect_vote,
         incorrect_nonce,
@@ -159,7 +639,7 @@ async fn test_binary_oracle() {
     let reveal_ix = binary_oracle::instruction::reveal(
         program_id,
         oracle.pubkey(),
-        node1.pubkey(),
+        node1_account_key,
         node1.pubkey(),
         vote,
         nonce,
@@ -187,7 +667,7 @@ async fn test_binary_oracle() {
     let reveal_ix1 = binary_oracle::instruction::reveal(
         program_id,
         oracle.pubkey(),
-        node1.pubkey(),
+        node1_account_key,
         node1.pubkey(),
         vote,
         nonce,
@@ -196,7 +676,7 @@ async fn test_binary_oracle() {
     let reveal_ix2 = binary_oracle::instruction::reveal(
         program_id,
         oracle.pubkey(),
-        node2.pubkey(),
+        node2_account_key,
         node2.pubkey(),
         vote,
         nonce,
@@ -222,6 +702,7 @@ async fn test_binary_oracle() {
         program_id,
         oracle.pubkey(),
         oracle_authority.pubkey(),
+        oracle_authority.pubkey(),
     );
 
     let mut transaction = Transaction::new_with_payer(
@@ -233,10 +714,5756 @@ async fn test_binary_oracle() {
 
     // Verify final balances
     let oracle_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
-    let node1_account = banks_client.get_account(node1.pubkey()).await.unwrap().unwrap();
-    let node2_account = banks_client.get_account(node2.pubkey()).await.unwrap().unwrap();
+    let node1_account = banks_client.get_account(node1_account_key).await.unwrap().unwrap();
+    let node2_account = banks_client.get_account(node2_account_key).await.unwrap().unwrap();
 
     let total_collateral = collateral * 2; // 2 nodes joined
     assert_eq!(oracle_account.lamports + node1_account.lamports + node2_account.lamports, 
                oracle_account_rent + total_collateral);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_estimate_reward_matches_payout() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+
+    // Node2 has not revealed yet, so only node1's public vote is used
+    let nodes = [node1, node2];
+    let estimate_true: u64 = env
+        .simulate(binary_oracle::instruction::estimate_reward(env.program_id, oracle.pubkey(), node2, true), &[])
+        .await;
+    let estimate_false: u64 = env
+        .simulate(binary_oracle::instruction::estimate_reward(env.program_id, oracle.pubkey(), node2, false), &[])
+        .await;
+    assert_eq!(estimate_true, collateral);
+    // Voting false would tie the round against node1, and a tie pays no reward
+    assert_eq!(estimate_false, 0);
+
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    let before = env.lamports(node2).await;
+
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &nodes).await.unwrap();
+
+    let after = env.lamports(node2).await;
+    assert_eq!(after - before, estimate_true);
+
+    // A node of another oracle can't be priced against this one
+    let other = env.initialize(&authority, collateral, reveal_duration, 3).await;
+    let (outsider, _) = env.join(other.pubkey()).await;
+    let ix = binary_oracle::instruction::estimate_reward(env.program_id, oracle.pubkey(), outsider, true);
+    let result = env.send(&[ix], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::NodeOracleMismatch);
+}
+
+#[tokio::test]
+async fn test_estimate_reward_is_zero_without_winners() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                quorum_bps: 7_000,
+                ..config(collateral, reveal_duration, 4)
+            },
+        )
+        .await
+        .unwrap();
+
+    let nodes = [
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+    ];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    let votes = [true, true, false, true];
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await;
+    }
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate().take(3) {
+        env.reveal(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await.unwrap();
+    }
+
+    // Three true votes of four clear the 70% quorum; two of four fall short and leave it inconclusive
+    let program_id = env.program_id;
+    let estimate_ix = |vote| binary_oracle::instruction::estimate_reward(program_id, oracle.pubkey(), nodes[3].0, vote);
+    let estimate_true: u64 = env.simulate(estimate_ix(true), &[]).await;
+    let estimate_false: u64 = env.simulate(estimate_ix(false), &[]).await;
+    assert_eq!(estimate_true, collateral);
+    assert_eq!(estimate_false, 0);
+
+    // Multi-outcome oracles can't be priced from a single bit
+    let multi = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                outcome_count: 3,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let (node, _) = env.join(multi.pubkey()).await;
+    let ix = binary_oracle::instruction::estimate_reward(env.program_id, multi.pubkey(), node, true);
+    assert_error(env.send(&[ix], &[]).await, binary_oracle::ErrorCode::WrongRevealMode);
+}
+
+#[tokio::test]
+async fn test_estimate_reward_matches_payout_after_fee_and_rebate() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                fee_bps: 1_000,
+                loser_rebate_bps: 2_000,
+                ..config(collateral, reveal_duration, 4)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (loser, loser_authority) = env.join(oracle.pubkey()).await;
+    let (colluder, colluder_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &colluder, &colluder_authority, true, [9u8; 32]).await;
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), colluder, &slasher, true, [9u8; 32]).await.unwrap();
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &loser, &loser_authority, false, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &loser, &loser_authority, false, [3u8; 32]).await.unwrap();
+
+    // The fee comes off the slashed pool and the loser's rebate off each winner's share
+    let estimate: u64 = env
+        .simulate(binary_oracle::instruction::estimate_reward(env.program_id, oracle.pubkey(), node2, true), &[])
+        .await;
+    assert!(estimate < collateral + collateral / 2);
+
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    let before = env.lamports(node2).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2, loser]).await.unwrap();
+    assert_eq!(env.lamports(node2).await - before, estimate);
+}
+
+#[tokio::test]
+async fn test_estimate_reward_matches_stake_weighted_payout() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                stake_weighted: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let large = env.funded_keypair(10_000_000_000).await;
+    env.try_join_with_stake(oracle.pubkey(), &large, 3 * collateral).await.unwrap();
+    let large_node = node_address(&env.program_id, &oracle.pubkey(), &large.pubkey());
+    let (small_node, small) = env.join(oracle.pubkey()).await;
+    let (colluder_node, colluder) = env.join(oracle.pubkey()).await;
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &colluder_node, &colluder, false, [9u8; 32]).await;
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), colluder_node, &slasher, false, [9u8; 32]).await.unwrap();
+    env.commit(oracle.pubkey(), &large_node, &large, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &small_node, &small, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &small_node, &small, true, [2u8; 32]).await.unwrap();
+
+    // The estimate prices the large node's unrevealed stake against the revealed one
+    let estimate: u64 = env
+        .simulate(binary_oracle::instruction::estimate_reward(env.program_id, oracle.pubkey(), large_node, true), &[])
+        .await;
+    assert_eq!(estimate, 3 * collateral + collateral * 3 / 4);
+
+    env.reveal(oracle.pubkey(), &large_node, &large, true, [1u8; 32]).await.unwrap();
+    let before = env.lamports(large_node).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[large_node, small_node]).await.unwrap();
+    assert_eq!(env.lamports(large_node).await - before, estimate);
+}
+
+#[tokio::test]
+async fn test_resolve_keeps_oracle_rent_exempt() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    // Another oracle's node would be paid out of this pool and eat into the
+    // rent-exempt reserve; it is rejected before any lamports move
+    let other = env.initialize(&authority, collateral, reveal_duration, 1).await;
+    let (foreign_node, foreign_authority) = env.join(other.pubkey()).await;
+    env.start_request(other.pubkey(), &authority).await;
+    env.commit(other.pubkey(), &foreign_node, &foreign_authority, true, [3u8; 32]).await;
+    env.reveal(other.pubkey(), &foreign_node, &foreign_authority, true, [3u8; 32]).await.unwrap();
+
+    env.set_time(reveal_duration + 1).await;
+    let result = env
+        .resolve(oracle.pubkey(), &authority, &[node1, node2, foreign_node])
+        .await;
+    assert_error(result, binary_oracle::ErrorCode::NodeOracleMismatch);
+
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2])
+        .await
+        .unwrap();
+    let rent = env.banks_client.get_rent().await.unwrap();
+    assert!(env.lamports(oracle.pubkey()).await >= rent.minimum_balance(Oracle::LEN));
+}
+
+#[tokio::test]
+async fn test_initialize_rejects_short_reveal_window() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let max_nodes = 5;
+    let per_node_seconds = 60;
+
+    // Below max_nodes * per_node_seconds
+    let result = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_reveal_seconds_per_node: per_node_seconds,
+                ..config(1_000_000, 299, max_nodes)
+            },
+        )
+        .await;
+    assert_error(result.map(|_| ()), binary_oracle::ErrorCode::RevealWindowTooShort);
+
+    // Exactly at the computed minimum
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_reveal_seconds_per_node: per_node_seconds,
+                ..config(1_000_000, 300, max_nodes)
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(env.oracle(oracle.pubkey()).await.config.reveal_duration, 300);
+}
+
+#[tokio::test]
+async fn test_migrate_collateral_between_oracles() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    // With pull_rewards the source oracle keeps holding the stake after resolve
+    let source = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                pull_rewards: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let destination = env.initialize(&authority, collateral, reveal_duration, 3).await;
+
+    let (node, node_authority) = env.join(source.pubkey()).await;
+    env.start_request(source.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(source.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(source.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(source.pubkey(), &authority, &[node]).await.unwrap();
+
+    let source_before = env.lamports(source.pubkey()).await;
+    let destination_before = env.lamports(destination.pubkey()).await;
+
+    let destination_node = node_address(&env.program_id, &destination.pubkey(), &node_authority.pubkey());
+    let ix = binary_oracle::instruction::migrate_collateral(
+        env.program_id,
+        source.pubkey(),
+        node,
+        destination.pubkey(),
+        destination_node,
+        membership_address(&env.program_id, &destination.pubkey(), &node_authority.pubkey()),
+        node_authority.pubkey(),
+    );
+    env.send(&[ix], &[&node_authority]).await.unwrap();
+
+    assert_eq!(source_before - env.lamports(source.pubkey()).await, collateral);
+    assert_eq!(env.lamports(destination.pubkey()).await - destination_before, collateral);
+    assert_eq!(env.node(node).await.state, NodeState::Withdrawn);
+    assert_eq!(env.node(destination_node).await.oracle, destination.pubkey());
+    assert_eq!(env.oracle(destination.pubkey()).await.total_nodes, 1);
+
+    // The same stake cannot be migrated twice, even into another oracle
+    let other = env.initialize(&authority, collateral, reveal_duration, 3).await;
+    let ix = binary_oracle::instruction::migrate_collateral(
+        env.program_id,
+        source.pubkey(),
+        node,
+        other.pubkey(),
+        node_address(&env.program_id, &other.pubkey(), &node_authority.pubkey()),
+        membership_address(&env.program_id, &other.pubkey(), &node_authority.pubkey()),
+        node_authority.pubkey(),
+    );
+    let result = env.send(&[ix], &[&node_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+}
+
+#[tokio::test]
+async fn test_migrate_collateral_after_resolve_credit_but_not_reclaim() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let source = env.initialize(&authority, collateral, reveal_duration, 2).await;
+    let destination = env.initialize(&authority, collateral, reveal_duration, 3).await;
+
+    let (node1, authority1) = env.join(source.pubkey()).await;
+    let (node2, authority2) = env.join(source.pubkey()).await;
+    env.start_request(source.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(source.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(source.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(source.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(source.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    // Resolve credits node1 directly; node2 is left out and reclaims instead
+    env.resolve(source.pubkey(), &authority, &[node1]).await.unwrap();
+    let reclaim_ix = binary_oracle::instruction::reclaim_collateral(
+        env.program_id,
+        source.pubkey(),
+        node2,
+        authority2.pubkey(),
+    );
+    env.send(&[reclaim_ix], &[&authority2]).await.unwrap();
+
+    let program_id = env.program_id;
+    let migrate_ix = |node: Pubkey, node_authority: &Keypair| {
+        binary_oracle::instruction::migrate_collateral(
+            program_id,
+            source.pubkey(),
+            node,
+            destination.pubkey(),
+            node_address(&program_id, &destination.pubkey(), &node_authority.pubkey()),
+            membership_address(&program_id, &destination.pubkey(), &node_authority.pubkey()),
+            node_authority.pubkey(),
+        )
+    };
+
+    // node1's payout sits on its node account, so it moves from there
+    let credited_before = env.lamports(node1).await;
+    let destination_before = env.lamports(destination.pubkey()).await;
+    env.send(&[migrate_ix(node1, &authority1)], &[&authority1]).await.unwrap();
+    assert_eq!(credited_before - env.lamports(node1).await, collateral);
+    assert_eq!(env.lamports(destination.pubkey()).await - destination_before, collateral);
+
+    // node2 already took its payout out, so nothing is left to migrate
+    let result = env.send(&[migrate_ix(node2, &authority2)], &[&authority2]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+    assert_eq!(env.oracle(destination.pubkey()).await.total_nodes, 1);
+}
+
+#[tokio::test]
+async fn test_migrate_collateral_goes_through_join_checks() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let source = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                pull_rewards: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let gated = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_reputation_to_join: 1,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let deferred = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                deferred_funding: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node, node_authority) = env.join(source.pubkey()).await;
+    env.start_request(source.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(source.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(source.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(source.pubkey(), &authority, &[node]).await.unwrap();
+
+    let program_id = env.program_id;
+    let migrate_ix = |destination: Pubkey| {
+        binary_oracle::instruction::migrate_collateral(
+            program_id,
+            source.pubkey(),
+            node,
+            destination,
+            node_address(&program_id, &destination, &node_authority.pubkey()),
+            membership_address(&program_id, &destination, &node_authority.pubkey()),
+            node_authority.pubkey(),
+        )
+    };
+
+    // A gated oracle wants the same reputation a direct join does
+    let result = env.send(&[migrate_ix(gated.pubkey())], &[&node_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::InsufficientReputation);
+
+    // A paused oracle takes no migrations
+    let pause_ix = |paused| binary_oracle::instruction::set_paused(program_id, deferred.pubkey(), authority.pubkey(), paused);
+    env.send(&[pause_ix(true)], &[&authority]).await.unwrap();
+    let result = env.send(&[migrate_ix(deferred.pubkey())], &[&node_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::Paused);
+    env.send(&[pause_ix(false)], &[&authority]).await.unwrap();
+
+    // With deferred funding the node lands pending and the whole payout goes to its authority
+    let source_before = env.lamports(source.pubkey()).await;
+    let deferred_before = env.lamports(deferred.pubkey()).await;
+    env.send(&[migrate_ix(deferred.pubkey())], &[&node_authority]).await.unwrap();
+    let migrated = env.node(node_address(&env.program_id, &deferred.pubkey(), &node_authority.pubkey())).await;
+    assert_eq!(migrated.state, NodeState::PendingJoin);
+    assert_eq!(env.lamports(deferred.pubkey()).await, deferred_before);
+    assert_eq!(env.oracle(deferred.pubkey()).await.total_nodes, 0);
+    assert_eq!(source_before - env.lamports(source.pubkey()).await, collateral);
+}
+
+#[tokio::test]
+async fn test_claim_abandoned_refunds_nodes() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let abandon_timeout = 100;
+
+    env.set_time(0).await;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                abandon_timeout,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let claim_ix = with_remaining_accounts(
+        binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), authority1.pubkey()),
+        &[node1, authority1.pubkey(), node2, authority2.pubkey()],
+    );
+
+    // The authority still has time to start the request
+    let result = env.send(&[claim_ix.clone()], &[&authority1]).await;
+    assert_error(result, binary_oracle::ErrorCode::AbandonTimeoutNotReached);
+
+    // Past the timeout, a node refunds everyone without the authority
+    env.set_time(abandon_timeout).await;
+    let balance1 = env.lamports(authority1.pubkey()).await;
+    let balance2 = env.lamports(authority2.pubkey()).await;
+    env.send(&[claim_ix], &[&authority1]).await.unwrap();
+
+    assert!(env.oracle(oracle.pubkey()).await.phase == Phase::Cancelled);
+    assert_eq!(env.lamports(authority1.pubkey()).await, balance1 + collateral);
+    assert_eq!(env.lamports(authority2.pubkey()).await, balance2 + collateral);
+    assert_eq!(env.node(node1).await.state, NodeState::Withdrawn);
+    assert_eq!(env.node(node2).await.state, NodeState::Withdrawn);
+}
+
+#[tokio::test]
+async fn test_claim_abandoned_timeout_overflow_is_an_error() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    env.set_time(1).await;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                abandon_timeout: i64::MAX,
+                ..config(1_000_000, 3600, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+
+    // created_at + abandon_timeout overflows; that must fail cleanly rather than panic
+    let claim_ix = with_remaining_accounts(
+        binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), node_authority.pubkey()),
+        &[node, node_authority.pubkey()],
+    );
+    let result = env.send(&[claim_ix], &[&node_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::ArithmeticOverflow);
+}
+
+#[tokio::test]
+async fn test_reveal_requires_outcome_stake() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                true_reveal_stake: collateral * 3,
+                false_reveal_stake: collateral,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+
+    // Stake can only be topped up before the reveal phase opens
+    let program_id = env.program_id;
+    let oracle_key = oracle.pubkey();
+    let top_up_ix = move |node: Pubkey, node_authority: &Keypair| {
+        binary_oracle::instruction::top_up_stake(
+            program_id,
+            oracle_key,
+            node,
+            node_authority.pubkey(),
+            collateral * 2,
+        )
+    };
+    let oracle_before = env.lamports(oracle.pubkey()).await;
+    let ix = top_up_ix(node1, &authority1);
+    env.send(&[ix], &[&authority1]).await.unwrap();
+    assert_eq!(env.lamports(oracle.pubkey()).await - oracle_before, collateral * 2);
+    assert_eq!(env.node(node1).await.stake, collateral * 3);
+
+    env.commit(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, true, [3u8; 32]).await;
+
+    // Claiming `true` needs the top-up, claiming `false` does not
+    let result = env.reveal(oracle.pubkey(), &node3, &authority3, true, [3u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::InsufficientStake);
+    let ix = top_up_ix(node3, &authority3);
+    let result = env.send(&[ix], &[&authority3]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidPhase);
+    env.reveal(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_topped_up_stake_is_returned_in_full() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (node, node_authority) in [(node1, &authority1), (node3, &authority3)] {
+        let ix = binary_oracle::instruction::top_up_stake(
+            env.program_id,
+            oracle.pubkey(),
+            node,
+            node_authority.pubkey(),
+            collateral * 2,
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+    }
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await.unwrap();
+
+    let node1_before = env.lamports(node1).await;
+    let node2_before = env.lamports(node2).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2, node3]).await.unwrap();
+
+    // The top-up rides on top of the flat reward rather than staying in the oracle
+    assert_eq!(env.lamports(node1).await - node1_before, collateral * 3);
+    assert_eq!(env.lamports(node2).await - node2_before, collateral);
+
+    // A losing revealer's reclaim returns its whole stake, top-up included
+    let before = env.lamports(authority3.pubkey()).await;
+    let ix = binary_oracle::instruction::reclaim_collateral(
+        env.program_id,
+        oracle.pubkey(),
+        node3,
+        authority3.pubkey(),
+    );
+    env.send(&[ix], &[&authority3]).await.unwrap();
+    assert_eq!(env.lamports(authority3.pubkey()).await - before, collateral * 3);
+}
+
+#[tokio::test]
+async fn test_random_tie_break_is_verifiable() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                tie_break_policy: TieBreakPolicy::RandomTieBreak,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await.unwrap();
+
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2])
+        .await
+        .unwrap();
+
+    // The outcome can be recomputed by anyone from public inputs, and is stable
+    let state = env.oracle(oracle.pubkey()).await;
+    let expected = random_tie_break_bit(&oracle.pubkey(), state.round, state.reveal_end_time);
+    assert_eq!(state.resolution_bit, expected);
+    assert_eq!(
+        random_tie_break_bit(&oracle.pubkey(), state.round, state.reveal_end_time),
+        expected
+    );
+}
+
+#[tokio::test]
+async fn test_slashing_is_capped_per_round() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                max_slash_per_round: collateral,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (_node3, slasher) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+
+    // The first slash uses up the whole cap
+    env.slash(oracle.pubkey(), node1, &slasher, true, [1u8; 32]).await.unwrap();
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_this_round, collateral);
+
+    let result = env.slash(oracle.pubkey(), node2, &slasher, true, [2u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::SlashCapReached);
+    assert_eq!(env.node(node2).await.state, NodeState::Committed);
+}
+
+#[tokio::test]
+async fn test_node_state_rejects_illegal_transitions() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000, 3600, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, slasher) = env.join(oracle.pubkey()).await;
+    assert_eq!(env.node(node1).await.state, NodeState::Joined);
+    env.start_request(oracle.pubkey(), &authority).await;
+
+    // Joined -> Slashed needs a commitment to prove against
+    let result = env.slash(oracle.pubkey(), node2, &slasher, true, [2u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::NotCommitted);
+
+    // Committed -> Committed
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    assert_eq!(env.node(node1).await.state, NodeState::Committed);
+    let result = env.try_commit(oracle.pubkey(), &node1, &authority1, false, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyCommitted);
+
+    // Slashed -> Slashed and Slashed -> Committed
+    env.slash(oracle.pubkey(), node1, &slasher, true, [1u8; 32]).await.unwrap();
+    assert_eq!(env.node(node1).await.state, NodeState::Slashed);
+    let result = env.slash(oracle.pubkey(), node1, &slasher, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadySlashed);
+    let result = env.try_commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidNodeState);
+
+    // Slashed -> Revealed is rejected once the reveal phase starts
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &slasher, false, [3u8; 32]).await;
+    let result = env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidNodeState);
+}
+
+#[tokio::test]
+async fn test_resolve_pays_keeper_bounty() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let resolve_bounty = 50_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                resolve_bounty,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    // Any keeper can resolve once the window closes and collects the bounty
+    let keeper = env.funded_keypair(1_000_000).await;
+    let keeper_before = env.lamports(keeper.pubkey()).await;
+    let node1_before = env.lamports(node1).await;
+    let node2_before = env.lamports(node2).await;
+
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &keeper, &[node1, node2])
+        .await
+        .unwrap();
+
+    assert_eq!(env.lamports(keeper.pubkey()).await - keeper_before, resolve_bounty);
+    assert_eq!(env.lamports(node1).await - node1_before, collateral);
+    assert_eq!(env.lamports(node2).await - node2_before, collateral);
+}
+
+#[tokio::test]
+async fn test_commitment_bound_to_request_start_time() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                bind_start_time: true,
+                ..config(1_000_000, 3600, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let start_time = 1_000;
+    env.set_time(start_time).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    assert_eq!(env.oracle(oracle.pubkey()).await.request_start_time, start_time);
+
+    // node1 stockpiled a commitment for a different start time
+    for (node, node_authority, hash) in [
+        (&node1, &authority1, bound_vote_hash(&oracle.pubkey(), &node1, true, &[1u8; 32], start_time - 1)),
+        (&node2, &authority2, bound_vote_hash(&oracle.pubkey(), &node2, true, &[2u8; 32], start_time)),
+    ] {
+        let ix = binary_oracle::instruction::commit(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+            hash,
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+    }
+
+    let result = env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidReveal);
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_roster_returns_node_summaries() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env.initialize(&authority, collateral, 3600, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+
+    let ix = with_remaining_accounts(
+        binary_oracle::instruction::roster(env.program_id, oracle.pubkey()),
+        &[node1, node2, node3],
+    );
+    let roster: Vec<NodeSummary> = env.simulate(ix, &[]).await;
+
+    assert_eq!(
+        roster,
+        vec![
+            NodeSummary { authority: authority1.pubkey(), state: NodeState::Committed, stake: collateral },
+            NodeSummary { authority: authority2.pubkey(), state: NodeState::Joined, stake: collateral },
+            NodeSummary { authority: authority3.pubkey(), state: NodeState::Joined, stake: collateral },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_unique_nonces_rejects_duplicate_reveal() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                unique_nonces_required: true,
+                ..config(1_000_000, 3600, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    let shared_nonce = [7u8; 32];
+    env.commit(oracle.pubkey(), &node1, &authority1, true, shared_nonce).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, shared_nonce).await;
+
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, shared_nonce).await.unwrap();
+    let result = env.reveal(oracle.pubkey(), &node2, &authority2, true, shared_nonce).await;
+    assert_error(result, binary_oracle::ErrorCode::DuplicateNonce);
+    assert_eq!(env.oracle(oracle.pubkey()).await.revealed_nonces.len(), 1);
+}
+
+#[tokio::test]
+async fn test_no_reveals_uses_configured_default() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                no_reveal_default: Some(true),
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, false, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await;
+
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2])
+        .await
+        .unwrap();
+
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+    assert!(state.is_resolved);
+    assert!(state.resolution_bit);
+}
+
+#[tokio::test]
+async fn test_no_reveals_without_default_cancels_and_refunds() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 2).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2])
+        .await
+        .unwrap();
+
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Cancelled);
+    assert!(!state.is_resolved);
+
+    // Cancelled oracles refund through the same path as abandoned ones
+    let balance1 = env.lamports(authority1.pubkey()).await;
+    let ix = with_remaining_accounts(
+        binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), authority1.pubkey()),
+        &[node1, authority1.pubkey()],
+    );
+    env.send(&[ix], &[&authority1]).await.unwrap();
+    assert_eq!(env.lamports(authority1.pubkey()).await, balance1 + collateral);
+}
+
+#[tokio::test]
+async fn test_rejoin_cooldown_after_leaving() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let rejoin_cooldown = 600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                rejoin_cooldown,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    env.set_time(1_000).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    let balance = env.lamports(node_authority.pubkey()).await;
+
+    let ix = binary_oracle::instruction::leave_network(
+        env.program_id,
+        oracle.pubkey(),
+        node,
+        membership_address(&env.program_id, &oracle.pubkey(), &node_authority.pubkey()),
+        node_authority.pubkey(),
+    );
+    env.send(&[ix], &[&node_authority]).await.unwrap();
+    assert!(env.lamports(node_authority.pubkey()).await >= balance + collateral);
+    assert_eq!(env.oracle(oracle.pubkey()).await.total_nodes, 0);
+
+    // Leaving closed the node account, but recreating it doesn't reset the cooldown
+    let result = env.try_join(oracle.pubkey(), &node_authority).await;
+    assert_error(result, binary_oracle::ErrorCode::RejoinCooldownActive);
+
+    env.set_time(1_000 + rejoin_cooldown).await;
+    env.try_join(oracle.pubkey(), &node_authority).await.unwrap();
+    assert_eq!(env.oracle(oracle.pubkey()).await.total_nodes, 1);
+}
+
+// Compute budget for resolve: a fixed overhead plus a per-node allowance
+const RESOLVE_BASE_CU: u64 = 20_000;
+const RESOLVE_CU_PER_NODE: u64 = 8_000;
+
+// Runs a full round with `committee_size` nodes all voting true and returns the
+// compute units consumed by resolve
+async fn resolve_compute_units(committee_size: usize) -> u64 {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize(&authority, 1_000_000, reveal_duration, committee_size as u64)
+        .await;
+
+    let mut nodes = Vec::with_capacity(committee_size);
+    for _ in 0..committee_size {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+
+    env.set_time(reveal_duration + 1).await;
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    let ix = with_remaining_accounts(
+        binary_oracle::instruction::resolve(env.program_id, oracle.pubkey(), authority.pubkey(), authority.pubkey()),
+        &node_keys,
+    );
+    env.send_metered(&[ix], &[&authority]).await
+}
+
+async fn assert_resolve_within_budget(committee_size: usize) {
+    let units = resolve_compute_units(committee_size).await;
+    let budget = RESOLVE_BASE_CU + committee_size as u64 * RESOLVE_CU_PER_NODE;
+    println!("resolve with {} nodes used {} compute units (budget {})", committee_size, units, budget);
+    assert!(units <= budget);
+}
+
+#[tokio::test]
+async fn test_resolve_compute_units_small_committee() {
+    assert_resolve_within_budget(3).await;
+}
+
+#[tokio::test]
+async fn test_resolve_compute_units_medium_committee() {
+    assert_resolve_within_budget(10).await;
+}
+
+#[tokio::test]
+async fn test_resolve_compute_units_large_committee() {
+    assert_resolve_within_budget(20).await;
+}
+
+#[tokio::test]
+async fn test_claim_missed_reward_after_omission() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await.unwrap();
+
+    // node2 is left out of resolve
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node3])
+        .await
+        .unwrap();
+    let reward = env.oracle(oracle.pubkey()).await.reward_per_node;
+    assert!(reward > 0);
+    assert!(!env.node(node2).await.rewarded);
+
+    let program_id = env.program_id;
+    let claim = |node: &Pubkey, node_authority: &Keypair| {
+        binary_oracle::instruction::claim_missed_reward(
+            program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+        )
+    };
+    let node2_before = env.lamports(node2).await;
+    env.send(&[claim(&node2, &authority2)], &[&authority2]).await.unwrap();
+    assert_eq!(env.lamports(node2).await - node2_before, reward);
+
+    // Nodes that were already paid cannot collect again
+    let result = env.send(&[claim(&node2, &authority2)], &[&authority2]).await;
+    assert!(result.is_err());
+    let result = env.send(&[claim(&node1, &authority1)], &[&authority1]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+    let result = env.send(&[claim(&node3, &authority3)], &[&authority3]).await;
+    assert_error(result, binary_oracle::ErrorCode::NotConsensusNode);
+}
+
+#[tokio::test]
+async fn test_deferred_join_funding() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                deferred_funding: true,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    // Joining only records intent; no collateral moves yet
+    let oracle_before = env.lamports(oracle.pubkey()).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    assert_eq!(env.lamports(oracle.pubkey()).await, oracle_before);
+    assert_eq!(env.node(node).await.state, NodeState::PendingJoin);
+    assert_eq!(env.oracle(oracle.pubkey()).await.total_nodes, 0);
+
+    // A pending node cannot vote
+    env.start_request(oracle.pubkey(), &authority).await;
+    let result = env.try_commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidNodeState);
+
+    // Stake topped up while the join is pending is kept once the collateral arrives
+    let top_up = collateral / 2;
+    let ix = binary_oracle::instruction::top_up_stake(env.program_id, oracle.pubkey(), node, node_authority.pubkey(), top_up);
+    env.send(&[ix], &[&node_authority]).await.unwrap();
+
+    // A separate funder (e.g. a wrapping program) completes the join
+    let funder = env.funded_keypair(10_000_000).await;
+    let ix = binary_oracle::instruction::fund_join(env.program_id, oracle.pubkey(), node, funder.pubkey());
+    env.send(&[ix], &[&funder]).await.unwrap();
+
+    assert_eq!(env.lamports(oracle.pubkey()).await - oracle_before, collateral + top_up);
+    let funded = env.node(node).await;
+    assert_eq!(funded.state, NodeState::Joined);
+    assert_eq!(funded.stake, collateral + top_up);
+    assert_eq!(env.oracle(oracle.pubkey()).await.total_nodes, 1);
+
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+}
+
+#[tokio::test]
+async fn test_reveal_deadline_overflow_is_rejected() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, MAX_REVEAL_DURATION, 1).await;
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+
+    // The final commit would push reveal_end_time past i64::MAX
+    env.set_time(i64::MAX - 10).await;
+    let result = env.try_commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidDeadline);
+
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Commit);
+    assert_eq!(state.reveal_end_time, 0);
+}
+
+#[tokio::test]
+async fn test_transition_bonus_paid_to_triggering_node() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let transition_bonus = 25_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                transition_bonus,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    assert_eq!(env.oracle(oracle.pubkey()).await.transition_node, Some(node2));
+
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    let node1_before = env.lamports(node1).await;
+    let node2_before = env.lamports(node2).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2])
+        .await
+        .unwrap();
+
+    // Only the node that made the final commit collects the bonus
+    assert_eq!(env.lamports(node1).await - node1_before, collateral);
+    assert_eq!(env.lamports(node2).await - node2_before, collateral + transition_bonus);
+}
+
+#[tokio::test]
+async fn test_produce_attestation_after_resolution() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 2).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    let (attestation, _) = Pubkey::find_program_address(
+        &[b"attestation", oracle.pubkey().as_ref(), &0u64.to_le_bytes()],
+        &env.program_id,
+    );
+    let payer = env.funded_keypair(1_000_000_000).await;
+    let attest_ix = binary_oracle::instruction::produce_attestation(
+        env.program_id,
+        oracle.pubkey(),
+        attestation,
+        payer.pubkey(),
+    );
+
+    // Nothing to attest before the oracle is complete
+    let result = env.send(&[attest_ix.clone()], &[&payer]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidPhase);
+
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2])
+        .await
+        .unwrap();
+    env.send(&[attest_ix], &[&payer]).await.unwrap();
+
+    let account = env.banks_client.get_account(attestation).await.unwrap().unwrap();
+    assert_eq!(account.owner, env.program_id);
+    let record = Attestation::try_deserialize(&mut account.data.as_slice()).unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(record.oracle, oracle.pubkey());
+    assert!(record.resolution_bit);
+    assert_eq!(
+        record.binding,
+        attestation_binding(&oracle.pubkey(), state.round, state.resolution_bit, state.reveal_end_time)
+    );
+}
+
+#[test]
+fn test_all_committed_guards_commit_count_invariant() {
+    assert!(!all_committed(1, 3).unwrap());
+    assert!(all_committed(3, 3).unwrap());
+
+    // An overcount must surface as an error instead of silently skipping the transition
+    let err = all_committed(4, 3).unwrap_err();
+    assert_eq!(err, binary_oracle::ErrorCode::CommitCountInvariantViolated.into());
+}
+
+#[test]
+fn test_node_len_covers_largest_node() {
+    let node = Node {
+        authority: Pubkey::new_unique(),
+        oracle: Pubkey::new_unique(),
+        vote_hash: Some([1u8; 32]),
+        vote: Some(u64::MAX),
+        state: NodeState::Revealed,
+        stake: u64::MAX,
+        rewarded: true,
+        sealed_vote: Some(SealedVote { ciphertext: [2u8; 33], key_hash: [3u8; 32] }),
+        slashed_by: Some(Pubkey::new_unique()),
+        reputation_recorded: true,
+        slash_witnesses: vec![Pubkey::new_unique(); MAX_SLASH_WITNESSES],
+        claimed: true,
+        committed_at: i64::MAX,
+        revealed_at: i64::MAX,
+        operator_recorded: true,
+        slashed_amount: u64::MAX,
+        slash_reward: u64::MAX,
+        slash_reason: Some(SlashReason::Collusion),
+        pre_slash_state: Some(NodeState::Revealed),
+    };
+    assert_eq!(node.try_to_vec().unwrap().len(), Node::LEN);
+
+    // Only the per-node reservations come on top of the fixed oracle layout
+    let mut config = config(1_000_000, 3600, 0);
+    assert_eq!(Oracle::space(&config), Oracle::LEN);
+    config.max_nodes = 3;
+    assert_eq!(Oracle::space(&config), Oracle::LEN + 3 * VoteRecord::LEN);
+}
+
+async fn resolved_oracle(env: &mut TestEnv, config: OracleConfig) -> (Keypair, Keypair) {
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let reveal_duration = config.reveal_duration;
+    let oracle = env.initialize_with(&authority, config).await.unwrap();
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+    (oracle, authority)
+}
+
+#[tokio::test]
+async fn test_finalize_only_after_undisputed_window() {
+    let mut env = TestEnv::new().await;
+    let reveal_duration = 3600;
+    let dispute_window = 600;
+    let resolved_at = reveal_duration + 1;
+    let dispute_config = OracleConfig {
+        dispute_window,
+        dispute_bond: 100_000,
+        ..config(1_000_000, reveal_duration, 1)
+    };
+
+    // Undisputed: finalize is rejected inside the window and succeeds after it
+    let (oracle, _) = resolved_oracle(&mut env, dispute_config.clone()).await;
+    let finalize_ix = binary_oracle::instruction::finalize(env.program_id, oracle.pubkey());
+    let result = env.send(&[finalize_ix.clone()], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::DisputeWindowOpen);
+
+    assert!(env.oracle(oracle.pubkey()).await.phase == Phase::Disputable);
+    assert_eq!(env.oracle(oracle.pubkey()).await.dispute_end_time, resolved_at + dispute_window);
+
+    env.set_time(resolved_at + dispute_window).await;
+    env.send(&[finalize_ix], &[]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+    assert_eq!(state.finalized_at, Some(resolved_at + dispute_window));
+
+    // Disputed: a pending dispute blocks finalize even after the window
+    let (oracle, _) = resolved_oracle(&mut env, dispute_config).await;
+    let disputer = env.funded_keypair(1_000_000_000).await;
+    let dispute_ix = binary_oracle::instruction::raise_dispute(env.program_id, oracle.pubkey(), disputer.pubkey());
+    env.send(&[dispute_ix], &[&disputer]).await.unwrap();
+
+    env.set_time(resolved_at + dispute_window).await;
+    let finalize_ix = binary_oracle::instruction::finalize(env.program_id, oracle.pubkey());
+    let result = env.send(&[finalize_ix], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::DisputePending);
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Disputable);
+    assert_eq!(state.finalized_at, None);
+}
+
+#[tokio::test]
+async fn test_authority_settles_escalated_dispute() {
+    let mut env = TestEnv::new().await;
+    let reveal_duration = 3600;
+    let dispute_bond = 100_000;
+    let dispute_config = OracleConfig {
+        dispute_window: 600,
+        dispute_bond,
+        ..config(1_000_000, reveal_duration, 1)
+    };
+
+    let (oracle, authority) = resolved_oracle(&mut env, dispute_config).await;
+    let disputer = env.funded_keypair(1_000_000_000).await;
+    let dispute_ix = binary_oracle::instruction::raise_dispute(env.program_id, oracle.pubkey(), disputer.pubkey());
+    env.send(&[dispute_ix], &[&disputer]).await.unwrap();
+
+    // Only the pending disputer can be refunded
+    let intruder = env.funded_keypair(1_000_000_000).await;
+    let wrong_ix = binary_oracle::instruction::settle_dispute(
+        env.program_id,
+        oracle.pubkey(),
+        authority.pubkey(),
+        intruder.pubkey(),
+0,
+    );
+    let result = env.send(&[wrong_ix], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::NotDisputer);
+
+    // Upholding the dispute flips the outcome and returns the bond
+    let disputer_before = env.lamports(disputer.pubkey()).await;
+    let settle_ix = binary_oracle::instruction::settle_dispute(
+        env.program_id,
+        oracle.pubkey(),
+        authority.pubkey(),
+        disputer.pubkey(),
+0,
+    );
+    env.send(&[settle_ix], &[&authority]).await.unwrap();
+
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+    assert!(!state.resolution_bit);
+    assert!(state.finalized_at.is_some());
+    assert_eq!(env.lamports(disputer.pubkey()).await, disputer_before + dispute_bond);
+}
+
+#[tokio::test]
+async fn test_upheld_dispute_pays_the_new_consensus_side() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                dispute_window: 600,
+                dispute_bond: 100_000,
+                ..config(collateral, reveal_duration, 4)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    let (silent, silent_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await;
+    env.commit(oracle.pubkey(), &silent, &silent_authority, true, [4u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await.unwrap();
+
+    // The silent committer's collateral is the pool the winning side splits
+    env.set_time(reveal_duration + 1).await;
+    let nodes = [node1, node2, node3, silent];
+    let slash_ix = with_remaining_accounts(
+        binary_oracle::instruction::slash_no_reveal(env.program_id, oracle.pubkey()),
+        &nodes,
+    );
+    env.send(&[slash_ix], &[]).await.unwrap();
+    let node1_before = env.lamports(node1).await;
+    env.resolve(oracle.pubkey(), &authority, &nodes).await.unwrap();
+    // Nothing is paid while the outcome can still be disputed
+    assert_eq!(env.lamports(node1).await, node1_before);
+    assert!(env.oracle(oracle.pubkey()).await.resolution_bit);
+
+    let disputer = env.funded_keypair(1_000_000_000).await;
+    let dispute_ix = binary_oracle::instruction::raise_dispute(env.program_id, oracle.pubkey(), disputer.pubkey());
+    env.send(&[dispute_ix], &[&disputer]).await.unwrap();
+    let settle_ix = binary_oracle::instruction::settle_dispute(
+        env.program_id,
+        oracle.pubkey(),
+        authority.pubkey(),
+        disputer.pubkey(),
+0,
+    );
+    env.send(&[settle_ix], &[&authority]).await.unwrap();
+
+    // node3 alone is in consensus now and takes the whole pool; the old winners get their deposit back
+    let mut payouts = Vec::new();
+    for (node, node_authority) in [(node1, &authority1), (node2, &authority2), (node3, &authority3)] {
+        let before = env.lamports(node_authority.pubkey()).await;
+        let ix = binary_oracle::instruction::reclaim_collateral(
+            env.program_id,
+            oracle.pubkey(),
+            node,
+            node_authority.pubkey(),
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+        payouts.push(env.lamports(node_authority.pubkey()).await - before);
+    }
+    assert_eq!(payouts, vec![collateral, collateral, collateral + collateral]);
+}
+
+#[tokio::test]
+async fn test_settle_dispute_picks_any_valid_outcome() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                outcome_count: 3,
+                dispute_window: 600,
+                dispute_bond: 100_000,
+                ..config(collateral, reveal_duration, 1)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    let ix = binary_oracle::instruction::commit(
+        env.program_id,
+        oracle.pubkey(),
+        node,
+        node_authority.pubkey(),
+        outcome_hash(&oracle.pubkey(), &node, 0, &[1u8; 32]),
+    );
+    env.send(&[ix], &[&node_authority]).await.unwrap();
+    let ix = binary_oracle::instruction::reveal_outcome(env.program_id, oracle.pubkey(), node, node_authority.pubkey(), 0, [1u8; 32]);
+    env.send(&[ix], &[&node_authority]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+
+    let disputer = env.funded_keypair(1_000_000_000).await;
+    let dispute_ix = binary_oracle::instruction::raise_dispute(env.program_id, oracle.pubkey(), disputer.pubkey());
+    env.send(&[dispute_ix], &[&disputer]).await.unwrap();
+    let program_id = env.program_id;
+    let settle_ix = |resolution: u64| {
+        binary_oracle::instruction::settle_dispute(program_id, oracle.pubkey(), authority.pubkey(), disputer.pubkey(), resolution)
+    };
+
+    // Outcomes past outcome_count are rejected
+    let result = env.send(&[settle_ix(3)], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidDisputeResolution);
+
+    // Any other outcome can replace the plurality, not just 0 or 1
+    env.send(&[settle_ix(2)], &[&authority]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+    assert_eq!(state.resolution_outcome, 2);
+    assert_eq!(state.resolution_mask, 2);
+}
+
+#[tokio::test]
+async fn test_held_loser_reclaims_its_rebate() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let dispute_window = 600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                dispute_window,
+                dispute_bond: 100_000,
+                loser_rebate_bps: 5_000,
+                ..config(collateral, reveal_duration, 4)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (loser, loser_authority) = env.join(oracle.pubkey()).await;
+    let (silent, silent_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &loser, &loser_authority, false, [3u8; 32]).await;
+    env.commit(oracle.pubkey(), &silent, &silent_authority, true, [4u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &loser, &loser_authority, false, [3u8; 32]).await.unwrap();
+
+    env.set_time(reveal_duration + 1).await;
+    let nodes = [node1, node2, loser, silent];
+    let slash_ix = with_remaining_accounts(
+        binary_oracle::instruction::slash_no_reveal(env.program_id, oracle.pubkey()),
+        &nodes,
+    );
+    env.send(&[slash_ix], &[]).await.unwrap();
+    env.resolve(oracle.pubkey(), &authority, &nodes).await.unwrap();
+    let rebate = env.oracle(oracle.pubkey()).await.loser_rebate;
+    assert!(rebate > 0);
+
+    env.set_time(reveal_duration + 1 + dispute_window).await;
+    let finalize_ix = binary_oracle::instruction::finalize(env.program_id, oracle.pubkey());
+    env.send(&[finalize_ix], &[]).await.unwrap();
+
+    // Resolve held the loser's rebate along with its deposit, so reclaim pays both
+    let before = env.lamports(loser_authority.pubkey()).await;
+    let ix = binary_oracle::instruction::reclaim_collateral(env.program_id, oracle.pubkey(), loser, loser_authority.pubkey());
+    env.send(&[ix], &[&loser_authority]).await.unwrap();
+    assert_eq!(env.lamports(loser_authority.pubkey()).await - before, collateral + rebate);
+}
+
+#[tokio::test]
+async fn test_auto_reveal_from_sealed_vote() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let auto_reveal_window = 300;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                auto_reveal_window,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await;
+
+    // node2 seals its vote under a key it hands to a keeper, then goes offline
+    let key = [9u8; 32];
+    let mut plaintext = [0u8; 33];
+    plaintext[1..].copy_from_slice(&[2u8; 32]);
+    let ciphertext = sealed_vote_ciphertext(&plaintext, &key, &node2);
+    let seal_ix = binary_oracle::instruction::submit_sealed_vote(
+        env.program_id,
+        oracle.pubkey(),
+        node2,
+        authority2.pubkey(),
+        ciphertext,
+        hash(&key).to_bytes(),
+    );
+    env.send(&[seal_ix], &[&authority2]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+
+    let keeper = env.funded_keypair(1_000_000).await;
+    let program_id = env.program_id;
+    let auto_reveal_ix = |key: [u8; 32]| {
+        binary_oracle::instruction::auto_reveal(program_id, oracle.pubkey(), node2, keeper.pubkey(), key)
+    };
+
+    // Too early: the node still has time to reveal on its own
+    let result = env.send(&[auto_reveal_ix(key)], &[&keeper]).await;
+    assert_error(result, binary_oracle::ErrorCode::AutoRevealNotOpen);
+
+    env.set_time(reveal_duration - auto_reveal_window).await;
+    let result = env.send(&[auto_reveal_ix([8u8; 32])], &[&keeper]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidEscrowKey);
+
+    env.send(&[auto_reveal_ix(key)], &[&keeper]).await.unwrap();
+    let state = env.node(node2).await;
+    assert_eq!(state.state, NodeState::Revealed);
+    assert_eq!(state.vote, Some(0));
+}
+
+#[tokio::test]
+async fn test_resolve_requires_distinct_authorities() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_distinct_authorities: 2,
+                allow_minority_resolve: true,
+                ..config(1_000_000, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+
+    // Only one distinct authority revealed
+    env.set_time(reveal_duration + 1).await;
+    let nodes = [node1, node2];
+    let result = env.resolve(oracle.pubkey(), &authority, &nodes).await;
+    assert_error(result, binary_oracle::ErrorCode::InsufficientDistinctVoters);
+    assert!(!env.oracle(oracle.pubkey()).await.is_resolved);
+}
+
+#[tokio::test]
+async fn test_overturn_slash_restores_node_and_forfeits_bond() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000;
+    let slash_bond = 50_000;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                slash_bond,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (_node2, slasher) = env.join(oracle.pubkey()).await;
+    let (_node3, _) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+
+    let node_before = env.lamports(node1).await;
+    let slasher_before = env.lamports(slasher.pubkey()).await;
+    env.slash(oracle.pubkey(), node1, &slasher, true, [1u8; 32]).await.unwrap();
+    assert_eq!(env.lamports(slasher.pubkey()).await, slasher_before - slash_bond);
+    assert_eq!(env.node(node1).await.slashed_by, Some(slasher.pubkey()));
+
+    // Only the oracle authority can overturn
+    let program_id = env.program_id;
+    let overturn_ix = |signer: Pubkey| {
+        binary_oracle::instruction::overturn_slash(program_id, oracle.pubkey(), node1, signer)
+    };
+    let ix = overturn_ix(slasher.pubkey());
+    let result = env.send(&[ix], &[&slasher]).await;
+    assert!(result.is_err());
+
+    let ix = overturn_ix(authority.pubkey());
+    env.send(&[ix], &[&authority]).await.unwrap();
+
+    // The node is made whole plus the bond, the slasher keeps nothing
+    let state = env.node(node1).await;
+    assert_eq!(state.state, NodeState::Committed);
+    assert_eq!(state.slashed_by, None);
+    assert_eq!(env.lamports(node1).await, node_before + slash_bond);
+    assert_eq!(env.lamports(slasher.pubkey()).await, slasher_before - slash_bond);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_this_round, 0);
+}
+
+#[tokio::test]
+async fn test_overturned_partial_collusion_slash_returns_exact_pool_share() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                slash_bps: 5_000,
+                slasher_reward_bps: 1_000,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (_node2, slasher) = env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.slash(oracle.pubkey(), node1, &slasher, true, [1u8; 32]).await.unwrap();
+
+    let seized = collateral / 2;
+    let slashed = env.node(node1).await;
+    assert_eq!(slashed.slash_reason, Some(SlashReason::Collusion));
+    assert_eq!((slashed.slashed_amount, slashed.slash_reward), (seized, seized / 10));
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, seized - seized / 10);
+
+    let ix = binary_oracle::instruction::overturn_slash(env.program_id, oracle.pubkey(), node1, authority.pubkey());
+    env.send(&[ix], &[&authority]).await.unwrap();
+    let reinstated = env.node(node1).await;
+    assert_eq!(reinstated.state, NodeState::Committed);
+    // With no witness bond to cover it, the slasher's reward already paid comes out of the stake
+    assert_eq!(reinstated.stake, collateral - seized / 10);
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.slashed_pool, 0);
+    assert_eq!(state.slashed_this_round, 0);
+}
+
+#[tokio::test]
+async fn test_events_carry_schema_version() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000, 3600, 2).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (_node2, slasher) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+
+    let slash_ix = binary_oracle::instruction::slash_colluding(
+        env.program_id,
+        oracle.pubkey(),
+        node1,
+        slasher.pubkey(),
+        true,
+        [1u8; 32],
+    );
+    let events: Vec<NodeSlashed> = env.send_with_events(&[slash_ix], &[&slasher]).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_version, EVENT_VERSION);
+
+    // With no dispute window a resolved oracle can be finalized straight away
+    let (oracle, _) = resolved_oracle(&mut env, config(1_000_000, 3600, 1)).await;
+    let finalize_ix = binary_oracle::instruction::finalize(env.program_id, oracle.pubkey());
+    let events: Vec<ResolutionFinalized> = env.send_with_events(&[finalize_ix], &[]).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_version, EVENT_VERSION);
+}
+
+#[tokio::test]
+async fn test_resolve_counts_votes_of_closed_nodes() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await.unwrap();
+    assert_eq!(env.oracle(oracle.pubkey()).await.votes_snapshot.len(), 3);
+
+    // node1 and node2 are gone by resolve time; only node3 can still be passed
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node3]).await.unwrap();
+
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.is_resolved);
+    assert!(state.resolution_bit);
+}
+
+#[tokio::test]
+async fn test_resolve_requires_min_reveal_slots() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let min_reveal_slots = 50;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_reveal_slots,
+                ..config(1_000_000, reveal_duration, 1)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_clock(100, 0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+
+    // The timestamp says the window closed, but too few slots have actually passed
+    env.set_clock(120, reveal_duration + 1).await;
+    let result = env.resolve(oracle.pubkey(), &authority, &[node]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealSlotsNotElapsed);
+
+    env.set_clock(100 + min_reveal_slots, reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+    assert!(env.oracle(oracle.pubkey()).await.is_resolved);
+}
+
+#[tokio::test]
+async fn test_slash_callback_records_slash() {
+    let callback_program = Pubkey::new_unique();
+    let mut env = TestEnv::with_slash_recorder(callback_program).await;
+    let authority = Keypair::new();
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                slash_callback_program: Some(callback_program),
+                ..config(1_000, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (_node3, slasher) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+
+    let program_id = env.program_id;
+    let slash_ix = |node: Pubkey, nonce: [u8; 32]| {
+        binary_oracle::instruction::slash_colluding(program_id, oracle.pubkey(), node, slasher.pubkey(), true, nonce)
+    };
+
+    // The callback program sees the slash within the same transaction
+    let ix = with_readonly_accounts(slash_ix(node1, [1u8; 32]), &[callback_program]);
+    let logs = env.send_with_logs(&[ix], &[&slasher]).await;
+    let expected = format!("recorded slash of {} for Collusion", node1);
+    assert!(logs.iter().any(|log| log.contains(&expected)));
+
+    // Without the callback program the slash still lands and the failure is reported
+    let ix = slash_ix(node2, [2u8; 32]);
+    let events: Vec<SlashCallbackFailed> = env.send_with_events(&[ix], &[&slasher]).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].slashed_node, node2);
+    assert_eq!(env.node(node2).await.state, NodeState::Slashed);
+}
+
+#[tokio::test]
+async fn test_loser_rebate_conserves_pool() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                loser_rebate_bps: 1_000,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await.unwrap();
+
+    let nodes = [node1, node2, node3];
+    let mut before = Vec::new();
+    for node in nodes.iter() {
+        before.push(env.lamports(*node).await);
+    }
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &nodes).await.unwrap();
+
+    let mut gained = Vec::new();
+    for (node, before) in nodes.iter().zip(before) {
+        gained.push(env.lamports(*node).await - before);
+    }
+
+    // Winners each give up 10% of their returned deposit, which the single loser receives
+    let gross = collateral;
+    assert_eq!(gained[0], gross - gross / 10);
+    assert_eq!(gained[1], gross - gross / 10);
+    assert_eq!(gained[2], 2 * (gross / 10));
+    assert_eq!(gained.iter().sum::<u64>(), gross * 2);
+}
+
+#[tokio::test]
+async fn test_price_trigger_starts_request() {
+    let below_feed = Pubkey::new_unique();
+    let above_feed = Pubkey::new_unique();
+    let threshold = 50_000;
+    let mut env = TestEnv::with_accounts(vec![
+        (below_feed, mock_price_account(threshold - 1)),
+        (above_feed, mock_price_account(threshold + 1)),
+    ])
+    .await;
+    let authority = Keypair::new();
+    let triggered_config = |price_account: Pubkey| OracleConfig {
+        price_trigger: Some(PriceTrigger {
+            price_account,
+            threshold,
+            trigger_above: true,
+        }),
+        ..config(1_000_000, 3600, 2)
+    };
+
+    // Price still below the threshold: the request stays in Precommit
+    let waiting = env.initialize_with(&authority, triggered_config(below_feed)).await.unwrap();
+    let ix = binary_oracle::instruction::try_trigger(env.program_id, waiting.pubkey(), below_feed);
+    let result = env.send(&[ix], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::PriceTriggerNotMet);
+    assert!(env.oracle(waiting.pubkey()).await.phase == Phase::Precommit);
+
+    // Only the configured feed is accepted
+    let crossed = env.initialize_with(&authority, triggered_config(above_feed)).await.unwrap();
+    let ix = binary_oracle::instruction::try_trigger(env.program_id, crossed.pubkey(), below_feed);
+    let result = env.send(&[ix], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::PriceAccountMismatch);
+
+    env.set_time(1_000).await;
+    let ix = binary_oracle::instruction::try_trigger(env.program_id, crossed.pubkey(), above_feed);
+    env.send(&[ix], &[]).await.unwrap();
+    let state = env.oracle(crossed.pubkey()).await;
+    assert!(state.phase == Phase::Commit);
+    assert_eq!(state.request_start_time, 1_000);
+}
+
+#[test]
+fn test_reveal_deadline_combines_pause_and_extension() {
+    let started_at = 1_000;
+    let reveal_duration = 3_600;
+    assert_eq!(reveal_deadline(started_at, reveal_duration, 0, 0).unwrap(), 4_600);
+
+    // Extensions and pauses both push the deadline out, and compose additively
+    assert_eq!(reveal_deadline(started_at, reveal_duration, 600, 0).unwrap(), 5_200);
+    assert_eq!(reveal_deadline(started_at, reveal_duration, 0, 120).unwrap(), 4_720);
+    assert_eq!(reveal_deadline(started_at, reveal_duration, 600, 120).unwrap(), 5_320);
+
+    let err = reveal_deadline(started_at, i64::MAX - 1_000, 600, 0).unwrap_err();
+    assert_eq!(err, binary_oracle::ErrorCode::InvalidDeadline.into());
+}
+
+#[tokio::test]
+async fn test_recompute_deadline_matches_reveal_start() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 1).await;
+    let recompute_ix = binary_oracle::instruction::recompute_deadline(env.program_id, oracle.pubkey());
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    let result = env.send(&[recompute_ix.clone()], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidPhase);
+
+    env.set_time(500).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    // Recomputing later derives the same deadline from the stored components
+    env.set_time(900).await;
+    env.send(&[recompute_ix], &[]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.reveal_started_at, 500);
+    assert_eq!(state.reveal_end_time, 500 + reveal_duration);
+}
+
+#[tokio::test]
+async fn test_join_requires_min_reputation() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let reveal_duration = 3600;
+
+    // A veteran earns one reputation point on a completed oracle
+    let first = env.initialize(&authority, 1_000_000, reveal_duration, 1).await;
+    let (node, veteran) = env.join(first.pubkey()).await;
+    env.start_request(first.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(first.pubkey(), &node, &veteran, true, [1u8; 32]).await;
+    env.reveal(first.pubkey(), &node, &veteran, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(first.pubkey(), &authority, &[node]).await.unwrap();
+
+    let record_ix = binary_oracle::instruction::record_reputation(
+        env.program_id,
+        first.pubkey(),
+        node,
+        reputation_address(&env.program_id, &veteran.pubkey()),
+        authority.pubkey(),
+    );
+    env.send(&[record_ix], &[&authority]).await.unwrap();
+
+    let gated = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_reputation_to_join: 1,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let program_id = env.program_id;
+    let gated_join_ix = |node_authority: &Keypair| {
+        let ix = binary_oracle::instruction::join_network(
+            program_id,
+            gated.pubkey(),
+            node_address(&program_id, &gated.pubkey(), &node_authority.pubkey()),
+            membership_address(&program_id, &gated.pubkey(), &node_authority.pubkey()),
+            node_authority.pubkey(),
+            1_000_000,
+        );
+        with_readonly_accounts(ix, &[reputation_address(&program_id, &node_authority.pubkey())])
+    };
+
+    // A newcomer has no reputation yet
+    let newcomer = env.funded_keypair(10_000_000_000).await;
+    let ix = gated_join_ix(&newcomer);
+    let result = env.send(&[ix], &[&newcomer]).await;
+    assert_error(result, binary_oracle::ErrorCode::InsufficientReputation);
+
+    let ix = gated_join_ix(&veteran);
+    env.send(&[ix], &[&veteran]).await.unwrap();
+    assert_eq!(env.oracle(gated.pubkey()).await.total_nodes, 1);
+}
+
+#[tokio::test]
+async fn test_slash_requires_witnesses() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                required_witnesses: 2,
+                ..config(1_000, 3600, 4)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (_node2, witness1) = env.join(oracle.pubkey()).await;
+    let (_node3, witness2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+
+    // One witness only records the proof
+    env.slash(oracle.pubkey(), node1, &witness1, true, [1u8; 32]).await.unwrap();
+    let state = env.node(node1).await;
+    assert_eq!(state.state, NodeState::Committed);
+    assert_eq!(state.slash_witnesses, vec![witness1.pubkey()]);
+
+    // The same witness can't count twice
+    let result = env.slash(oracle.pubkey(), node1, &witness1, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::DuplicateWitness);
+
+    env.slash(oracle.pubkey(), node1, &witness2, true, [1u8; 32]).await.unwrap();
+    let state = env.node(node1).await;
+    assert_eq!(state.state, NodeState::Slashed);
+    assert_eq!(state.slashed_by, Some(witness2.pubkey()));
+}
+
+#[tokio::test]
+async fn test_bitmask_resolves_per_bit_majority() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                num_bits: 4,
+                min_matching_bits: 3,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let masks = [0b1011u64, 0b1001, 0b0110];
+    let mut nodes = Vec::new();
+    for _ in masks.iter() {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, ((node, node_authority), mask)) in nodes.iter().zip(masks).enumerate() {
+        let ix = binary_oracle::instruction::commit(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+            mask_hash(&oracle.pubkey(), node, mask, &[i as u8; 32]),
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+    }
+
+    // Single-question reveals are rejected for a bitmask oracle
+    let (node, node_authority) = &nodes[0];
+    let result = env.reveal(oracle.pubkey(), node, node_authority, true, [0u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::WrongRevealMode);
+
+    for (i, ((node, node_authority), mask)) in nodes.iter().zip(masks).enumerate() {
+        let ix = binary_oracle::instruction::reveal_mask(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+            mask,
+            [i as u8; 32],
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+    }
+
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    let mut before = Vec::new();
+    for node in node_keys.iter() {
+        before.push(env.lamports(*node).await);
+    }
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &node_keys).await.unwrap();
+
+    // Bits 0, 1 and 3 have a majority; the first two nodes match on at least three bits
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.resolution_mask, 0b1011);
+    let reward = collateral;
+    assert_eq!(env.lamports(node_keys[0]).await - before[0], reward);
+    assert_eq!(env.lamports(node_keys[1]).await - before[1], reward);
+    assert_eq!(env.lamports(node_keys[2]).await, before[2]);
+}
+
+#[tokio::test]
+async fn test_late_resolution_penalizes_authority() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let resolve_deadline = 600;
+    let resolution_bond = 1_000_000;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                authority_resolves: true,
+                resolve_deadline,
+                resolution_bond,
+                late_penalty_bps: 5_000,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    let nodes = [node1, node2];
+    let program_id = env.program_id;
+    let penalize_ix = |caller: Pubkey| {
+        with_remaining_accounts(
+            binary_oracle::instruction::penalize_late_resolution(
+                program_id,
+                oracle.pubkey(),
+                authority.pubkey(),
+                caller,
+                authority.pubkey(),
+            ),
+            &nodes,
+        )
+    };
+
+    // Only the authority resolves, and nodes can't penalize before the deadline
+    env.set_time(reveal_duration + 1).await;
+    let result = env.resolve(oracle.pubkey(), &authority1, &nodes).await;
+    assert_error(result, binary_oracle::ErrorCode::UnauthorizedAccess);
+    let result = env.send(&[penalize_ix(authority1.pubkey())], &[&authority1]).await;
+    assert_error(result, binary_oracle::ErrorCode::ResolveDeadlineNotReached);
+
+    // The authority misses the deadline, so a node resolves and half the bond goes to the nodes
+    env.set_time(reveal_duration + resolve_deadline + 1).await;
+    let result = env.resolve(oracle.pubkey(), &authority, &nodes).await;
+    assert_error(result, binary_oracle::ErrorCode::ResolveDeadlinePassed);
+
+    let authority_before = env.lamports(authority.pubkey()).await;
+    let node1_before = env.lamports(node1).await;
+    let node2_before = env.lamports(node2).await;
+    env.send(&[penalize_ix(authority1.pubkey())], &[&authority1]).await.unwrap();
+
+    assert!(env.oracle(oracle.pubkey()).await.is_resolved);
+    let penalty_share = resolution_bond / 4;
+    assert_eq!(env.lamports(node1).await - node1_before, collateral + penalty_share);
+    assert_eq!(env.lamports(node2).await - node2_before, collateral + penalty_share);
+    assert_eq!(env.lamports(authority.pubkey()).await - authority_before, resolution_bond / 2);
+}
+
+#[tokio::test]
+async fn test_resolution_bond_only_backs_authority_resolution() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let resolution_bond = 1_000_000;
+    let commit_deadline = 600;
+
+    // Without authority_resolves there is nothing for the bond to back, so none is posted
+    let open = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                resolution_bond,
+                ..config(collateral, 3600, 2)
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(env.oracle(open.pubkey()).await.config.resolution_bond, 0);
+
+    // An authority-gated round cancelled before reveal hands the bond back
+    env.set_time(0).await;
+    let gated = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                authority_resolves: true,
+                resolve_deadline: 600,
+                resolution_bond,
+                commit_deadline,
+                ..config(collateral, 3600, 2)
+            },
+        )
+        .await
+        .unwrap();
+    env.join(gated.pubkey()).await;
+    env.start_request(gated.pubkey(), &authority).await;
+    env.set_time(commit_deadline).await;
+    let oracle_before = env.lamports(gated.pubkey()).await;
+    let cancel_ix = binary_oracle::instruction::cancel_request(env.program_id, gated.pubkey(), authority.pubkey());
+    env.send(&[cancel_ix], &[&authority]).await.unwrap();
+    assert_eq!(oracle_before - env.lamports(gated.pubkey()).await, resolution_bond);
+}
+
+#[tokio::test]
+async fn test_ordered_reveal_enforces_slots() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                ordered_reveal: true,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let nodes = [env.join(oracle.pubkey()).await, env.join(oracle.pubkey()).await];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+
+    // Each node gets one half of the window, chosen from the seed fixed at reveal start
+    let seed = env.oracle(oracle.pubkey()).await.reveal_seed;
+    let slot_seconds = reveal_duration / 2;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        let slot = reveal_slot(&seed, node, 2) as i64;
+
+        env.set_time((1 - slot) * slot_seconds).await;
+        let result = env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+        assert_error(result, binary_oracle::ErrorCode::RevealOutOfSlot);
+
+        env.set_time(slot * slot_seconds).await;
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_ordered_reveal_slots_follow_extended_window() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+
+    // A window shorter than one second per node leaves some nodes no slot at all
+    let result = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                ordered_reveal: true,
+                ..config(1_000_000, 2, 3)
+            },
+        )
+        .await
+        .map(|_| ());
+    assert_error(result, binary_oracle::ErrorCode::RevealWindowTooShort);
+
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                ordered_reveal: true,
+                max_reveal_extensions: 1,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let nodes = [env.join(oracle.pubkey()).await, env.join(oracle.pubkey()).await];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    let ix = binary_oracle::instruction::extend_reveal(env.program_id, oracle.pubkey(), authority.pubkey(), reveal_duration);
+    env.send(&[ix], &[&authority]).await.unwrap();
+
+    // Doubling the window doubles each slot, so the second half of each is still open
+    let seed = env.oracle(oracle.pubkey()).await.reveal_seed;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        let slot = reveal_slot(&seed, node, 2) as i64;
+        env.set_time(slot * reveal_duration + reveal_duration / 2).await;
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_auto_settle_refunds_losers_atomically() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                auto_settle: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node3, &authority3, false, [3u8; 32]).await.unwrap();
+
+    let nodes = [node1, node2, node3];
+    let mut before = Vec::new();
+    for node in nodes.iter() {
+        before.push(env.lamports(*node).await);
+    }
+    let oracle_before = env.lamports(oracle.pubkey()).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &nodes).await.unwrap();
+
+    // Everyone is settled in one pass and the whole pool leaves the oracle
+    let mut gained = Vec::new();
+    for (node, before) in nodes.iter().zip(before) {
+        gained.push(env.lamports(*node).await - before);
+        assert!(env.node(*node).await.rewarded);
+    }
+    assert_eq!(gained, vec![collateral, collateral, collateral]);
+    assert_eq!(oracle_before - env.lamports(oracle.pubkey()).await, 3 * collateral);
+}
+
+#[tokio::test]
+async fn test_config_returns_init_parameters() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let init_config = OracleConfig {
+        transition_bonus: 5_000,
+        loser_rebate_bps: 2_500,
+        auto_settle: true,
+        ..config(1_000_000, 3600, 3)
+    };
+    let oracle = env.initialize_with(&authority, init_config.clone()).await.unwrap();
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    // Round state has moved on, but the config is exactly what was initialized
+    let ix = binary_oracle::instruction::config(env.program_id, oracle.pubkey());
+    let returned: OracleConfig = env.simulate(ix, &[]).await;
+    assert_eq!(returned, init_config);
+}
+
+#[tokio::test]
+async fn test_late_reveal_earns_less() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                late_reveal_penalty_bps: 5_000,
+                late_reveal_window: 60,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let nodes = [
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+    ];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+
+    // Two nodes reveal early, the last one waits until the final second
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.set_time(if i < 2 { 10 } else { reveal_duration - 1 }).await;
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+
+    let keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    let mut before = Vec::new();
+    for key in keys.iter() {
+        before.push(env.lamports(*key).await);
+    }
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &keys).await.unwrap();
+
+    let mut gained = Vec::new();
+    for (key, before) in keys.iter().zip(before) {
+        gained.push(env.lamports(*key).await - before);
+    }
+    assert!(gained[2] < gained[0]);
+    assert_eq!(gained[0], gained[1]);
+    assert_eq!(gained.iter().sum::<u64>(), 3 * collateral);
+}
+
+#[tokio::test]
+async fn test_late_reveal_penalty_applies_to_missed_reward() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                late_reveal_penalty_bps: 5_000,
+                late_reveal_window: 60,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let nodes = [
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+    ];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.set_time(if i < 2 { 10 } else { reveal_duration - 1 }).await;
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+
+    // The late revealer is left out of resolve and collects through claim_missed_reward
+    let (late_node, late_authority) = &nodes[2];
+    let late_before = env.lamports(*late_node).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[nodes[0].0, nodes[1].0]).await.unwrap();
+    let ix = binary_oracle::instruction::claim_missed_reward(
+        env.program_id,
+        oracle.pubkey(),
+        *late_node,
+        late_authority.pubkey(),
+    );
+    env.send(&[ix], &[late_authority]).await.unwrap();
+
+    // It is paid the same penalized amount resolve would have paid, not the raw reward
+    let reward = env.oracle(oracle.pubkey()).await.reward_per_node;
+    assert_eq!(env.lamports(*late_node).await - late_before, reward - reward / 2);
+}
+
+#[tokio::test]
+async fn test_export_import_preserves_mid_reveal_oracle() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let oracle = env.initialize(&authority, collateral, 3600, 2).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+
+    let exported = env.oracle(oracle.pubkey()).await;
+    let lamports = env.lamports(oracle.pubkey()).await;
+    let export = Pubkey::find_program_address(&[b"export", oracle.pubkey().as_ref()], &env.program_id).0;
+
+    let ix = binary_oracle::instruction::export_state(env.program_id, oracle.pubkey(), export, authority.pubkey());
+    env.send(&[ix], &[&authority]).await.unwrap();
+    assert!(env.banks_client.get_account(oracle.pubkey()).await.unwrap().is_none());
+
+    // The import lands at a PDA, so only the authority signs
+    let imported_key = imported_address(&env.program_id, &oracle.pubkey());
+    let ix = binary_oracle::instruction::import_state(env.program_id, export, imported_key, authority.pubkey());
+    env.send(&[ix], &[&authority]).await.unwrap();
+    assert!(env.banks_client.get_account(export).await.unwrap().is_none());
+
+    // Phase, round state and funds all survive the round trip
+    let imported = env.oracle(imported_key).await;
+    assert!(imported.phase == Phase::Reveal);
+    assert_eq!(imported.migrated_from, Some(oracle.pubkey()));
+    assert_eq!(imported.committed_nodes, exported.committed_nodes);
+    assert_eq!(env.lamports(imported_key).await, lamports);
+
+    // Each node follows the oracle to its new address, taking its recorded reveal along
+    let new_node1 = env.import_node(imported_key, &node1, &authority1).await.unwrap();
+    let new_node2 = env.import_node(imported_key, &node2, &authority2).await.unwrap();
+    assert!(env.banks_client.get_account(node1).await.unwrap().is_none());
+    assert!(env.node(new_node1).await.state == NodeState::Revealed);
+    assert_eq!(env.oracle(imported_key).await.votes_snapshot[0].node, new_node1);
+
+    // The round carries on against the imported oracle
+    env.reveal(imported_key, &new_node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.set_time(3601).await;
+    env.resolve(imported_key, &authority, &[new_node1, new_node2]).await.unwrap();
+    assert!(env.oracle(imported_key).await.is_resolved);
+}
+
+#[tokio::test]
+async fn test_import_converts_version_1_export() {
+    let program_id = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let exported_key = Pubkey::new_unique();
+    let collateral = 1_000_000;
+    let funds = 2 * collateral;
+    let voter = Pubkey::new_unique();
+
+    // An oracle mid-reveal, serialized in the layout version 1 programs wrote
+    let legacy = LegacyOracleV1 {
+        authority: authority.pubkey(),
+        config: LegacyOracleConfigV1 {
+            collateral,
+            reveal_duration: 3600,
+            max_nodes: 2,
+            ..LegacyOracleConfigV1::default()
+        },
+        is_resolved: false,
+        resolution_bit: false,
+        resolution_mask: 0,
+        phase: Phase::Reveal,
+        reveal_end_time: 3600,
+        total_nodes: 2,
+        committed_nodes: 2,
+        created_at: 0,
+        round: 1,
+        slashed_this_round: 0,
+        request_start_time: 0,
+        reward_per_node: 0,
+        loser_rebate: 0,
+        transition_node: None,
+        resolved_at: 0,
+        dispute: None,
+        finalized_at: None,
+        reveal_start_slot: 0,
+        reveal_started_at: 0,
+        reveal_extension: 0,
+        paused_duration: 0,
+        reveal_seed: [0; 32],
+        revealed_nonces: Vec::new(),
+        votes_snapshot: vec![LegacyVoteRecordV1 {
+            node: voter,
+            authority: Pubkey::new_unique(),
+            vote: true,
+            mask: 1,
+            revealed_at: 10,
+        }],
+    };
+    let state = legacy.try_to_vec().unwrap();
+    let export_data = StateExport {
+        version: 1,
+        oracle: exported_key,
+        authority: authority.pubkey(),
+        space: 8 + state.len() as u64,
+        funds,
+        state,
+    };
+    let (mut env, export) = TestEnv::with_export(program_id, &export_data).await;
+
+    let imported_key = imported_address(&program_id, &exported_key);
+    let ix = binary_oracle::instruction::import_state(program_id, export, imported_key, authority.pubkey());
+    env.send(&[ix], &[&authority]).await.unwrap();
+
+    // The old fields carry over and the new ones take initialize's values
+    let imported = env.oracle(imported_key).await;
+    assert!(imported.phase == Phase::Reveal);
+    assert_eq!(imported.round, 1);
+    assert_eq!(imported.config.collateral, collateral);
+    assert_eq!(imported.config.fee_recipient, authority.pubkey());
+    assert_eq!(imported.config.fee_bps, 0);
+    assert_eq!(imported.revealed_nodes, 1);
+    assert_eq!(imported.slashed_pool, 0);
+    assert_eq!(imported.migrated_from, Some(exported_key));
+    assert_eq!(imported.votes_snapshot[0].node, voter);
+    assert_eq!(imported.votes_snapshot[0].stake, collateral);
+
+    // Sized for the current layout rather than the smaller version 1 account
+    let account = env.banks_client.get_account(imported_key).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + Oracle::space(&imported.config));
+    let rent = env.banks_client.get_rent().await.unwrap().minimum_balance(account.data.len());
+    assert_eq!(account.lamports, rent + funds);
+}
+
+#[tokio::test]
+async fn test_import_rejects_unknown_export_version() {
+    let program_id = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let exported_key = Pubkey::new_unique();
+    let export_data = StateExport {
+        version: EXPORT_VERSION + 1,
+        oracle: exported_key,
+        authority: authority.pubkey(),
+        space: 8 + Oracle::LEN as u64,
+        funds: 0,
+        state: Vec::new(),
+    };
+    let (mut env, export) = TestEnv::with_export(program_id, &export_data).await;
+
+    let imported_key = imported_address(&program_id, &exported_key);
+    let ix = binary_oracle::instruction::import_state(program_id, export, imported_key, authority.pubkey());
+    let result = env.send(&[ix], &[&authority]).await;
+    assert_error(result, ErrorCode::UnsupportedExportVersion);
+}
+
+#[tokio::test]
+async fn test_commit_fees_fund_resolve_bounty() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let commit_fee = 7_000;
+    let resolve_bounty = 10_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                commit_fee,
+                resolve_bounty,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    let before = env.lamports(authority1.pubkey()).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    assert!(before - env.lamports(authority1.pubkey()).await >= commit_fee);
+    assert_eq!(env.oracle(oracle.pubkey()).await.bounty_pool, 2 * commit_fee);
+
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    let node_before = env.lamports(node1).await;
+    let keeper = env.funded_keypair(1_000_000_000).await;
+    let keeper_before = env.lamports(keeper.pubkey()).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &keeper, &[node1, node2]).await.unwrap();
+
+    // The keeper collects the fees on top of the bounty; node rewards are untouched
+    assert_eq!(env.lamports(keeper.pubkey()).await - keeper_before, resolve_bounty + 2 * commit_fee);
+    assert_eq!(env.lamports(node1).await - node_before, collateral);
+    assert_eq!(env.oracle(oracle.pubkey()).await.bounty_pool, 0);
+}
+
+#[tokio::test]
+async fn test_abstainer_does_not_block_reveal() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+
+    let ix = binary_oracle::instruction::declare_abstain(
+        env.program_id,
+        oracle.pubkey(),
+        node3,
+        authority3.pubkey(),
+    );
+    env.send(&[ix], &[&authority3]).await.unwrap();
+    assert!(env.node(node3).await.state == NodeState::Abstained);
+
+    // Two commits now complete the committee
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Reveal);
+    assert_eq!(state.total_nodes, 2);
+
+    let result = env.try_commit(oracle.pubkey(), &node3, &authority3, true, [3u8; 32]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_resolve_requires_full_node_set() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                require_full_node_set: true,
+                allow_minority_resolve: true,
+                ..config(1_000_000, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let nodes = [
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+    ];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    let (node, node_authority) = &nodes[0];
+    env.reveal(oracle.pubkey(), node, node_authority, true, [0u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+
+    // The revealed node is covered by the snapshot, but the unrevealed ones must be passed
+    let keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    let result = env.resolve(oracle.pubkey(), &authority, &keys[..2]).await;
+    assert_error(result, binary_oracle::ErrorCode::IncompleteNodeSet);
+
+    env.resolve(oracle.pubkey(), &authority, &keys).await.unwrap();
+    assert!(env.oracle(oracle.pubkey()).await.is_resolved);
+}
+
+#[tokio::test]
+async fn test_full_node_set_accepts_abstainers() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                require_full_node_set: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let nodes = [
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+        env.join(oracle.pubkey()).await,
+    ];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    let (idle, idle_authority) = &nodes[2];
+    let ix = binary_oracle::instruction::declare_abstain(env.program_id, oracle.pubkey(), *idle, idle_authority.pubkey());
+    env.send(&[ix], &[idle_authority]).await.unwrap();
+    for (i, (node, node_authority)) in nodes[..2].iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes[..2].iter().enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+    env.set_time(reveal_duration + 1).await;
+
+    // The abstainer is passed in for its refund without counting against the committee
+    let keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    let idle_before = env.lamports(*idle).await;
+    env.resolve(oracle.pubkey(), &authority, &keys).await.unwrap();
+    assert!(env.oracle(oracle.pubkey()).await.is_resolved);
+    assert_eq!(env.lamports(*idle).await - idle_before, collateral);
+}
+
+#[tokio::test]
+async fn test_join_moves_collateral_through_system_program() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env.initialize(&authority, collateral, 3600, 3).await;
+
+    let node_authority = env.funded_keypair(10_000_000_000).await;
+    let node = node_address(&env.program_id, &oracle.pubkey(), &node_authority.pubkey());
+    let oracle_before = env.lamports(oracle.pubkey()).await;
+    let authority_before = env.lamports(node_authority.pubkey()).await;
+    env.try_join(oracle.pubkey(), &node_authority).await.unwrap();
+
+    // The authority also paid rent for the node and membership accounts it created
+    let membership = membership_address(&env.program_id, &oracle.pubkey(), &node_authority.pubkey());
+    let rent_paid = env.lamports(node).await + env.lamports(membership).await;
+    assert_eq!(env.lamports(oracle.pubkey()).await - oracle_before, collateral);
+    assert_eq!(authority_before - env.lamports(node_authority.pubkey()).await - rent_paid, collateral);
+}
+
+#[tokio::test]
+async fn test_reclaim_collateral_once_and_not_when_slashed() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, true, [3u8; 32]).await;
+    env.slash(oracle.pubkey(), node3, &authority1, true, [3u8; 32]).await.unwrap();
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2, node3])
+        .await
+        .unwrap();
+
+    let reclaim = |env: &TestEnv, node: &Pubkey, node_authority: &Keypair| {
+        binary_oracle::instruction::reclaim_collateral(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+        )
+    };
+
+    // The node's share moves on to its authority exactly once
+    let reward = env.oracle(oracle.pubkey()).await.reward_per_node;
+    let before = env.lamports(authority1.pubkey()).await;
+    let ix = reclaim(&env, &node1, &authority1);
+    env.send(&[ix], &[&authority1]).await.unwrap();
+    assert_eq!(env.lamports(authority1.pubkey()).await - before, reward);
+    assert!(env.node(node1).await.claimed);
+
+    let ix = reclaim(&env, &node1, &authority1);
+    assert_error(env.send(&[ix], &[&authority1]).await, binary_oracle::ErrorCode::AlreadyClaimed);
+
+    let ix = reclaim(&env, &node3, &authority3);
+    assert_error(env.send(&[ix], &[&authority3]).await, binary_oracle::ErrorCode::AlreadySlashed);
+}
+
+async fn resolve_with_optional_slash(slash: bool) -> (u64, u64, u64) {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, true, [3u8; 32]).await;
+    if slash {
+        env.slash(oracle.pubkey(), node3, &authority1, true, [3u8; 32]).await.unwrap();
+        assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, collateral);
+    }
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    let node1_before = env.lamports(node1).await;
+    let node2_before = env.lamports(node2).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2, node3])
+        .await
+        .unwrap();
+    (
+        env.lamports(node1).await - node1_before,
+        env.lamports(node2).await - node2_before,
+        env.oracle(oracle.pubkey()).await.slashed_pool,
+    )
+}
+
+#[tokio::test]
+async fn test_slashed_collateral_split_among_consensus_nodes() {
+    let collateral = 1_000_000;
+    let (gained1, gained2, slashed_pool) = resolve_with_optional_slash(true).await;
+    assert_eq!(gained1, collateral + collateral / 2);
+    assert_eq!(gained2, collateral + collateral / 2);
+    assert_eq!(slashed_pool, 0);
+}
+
+#[tokio::test]
+async fn test_without_slash_consensus_nodes_get_deposit_back() {
+    let collateral = 1_000_000;
+    let (gained1, gained2, slashed_pool) = resolve_with_optional_slash(false).await;
+    assert_eq!(gained1, collateral);
+    assert_eq!(gained2, collateral);
+    assert_eq!(slashed_pool, 0);
+}
+
+#[tokio::test]
+async fn test_silent_committer_collateral_goes_to_revealers() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 4).await;
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (silent, silent_authority) = env.join(oracle.pubkey()).await;
+    let (idle, idle_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &silent, &silent_authority, true, [3u8; 32]).await;
+    let ix = binary_oracle::instruction::declare_abstain(
+        env.program_id,
+        oracle.pubkey(),
+        idle,
+        idle_authority.pubkey(),
+    );
+    env.send(&[ix], &[&idle_authority]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    let nodes = [node1, node2, silent, idle];
+    let slash_ix = with_remaining_accounts(
+        binary_oracle::instruction::slash_no_reveal(env.program_id, oracle.pubkey()),
+        &nodes,
+    );
+    let result = env.send(&[slash_ix.clone()], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealPhaseNotClosed);
+
+    env.set_time(reveal_duration + 1).await;
+    env.send(&[slash_ix], &[]).await.unwrap();
+
+    // Only the node that committed and went quiet is slashed
+    assert_eq!(env.node(silent).await.state, NodeState::Slashed);
+    assert_eq!(env.node(idle).await.state, NodeState::Abstained);
+    assert_eq!(env.node(node1).await.state, NodeState::Revealed);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, collateral);
+
+    let before = env.lamports(node1).await;
+    env.resolve(oracle.pubkey(), &authority, &nodes).await.unwrap();
+    assert_eq!(env.lamports(node1).await - before, collateral + collateral / 2);
+}
+
+#[tokio::test]
+async fn test_slash_no_reveal_is_capped_and_overturned_exactly() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                max_slash_per_round: collateral,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    let (silent1, silent_authority1) = env.join(oracle.pubkey()).await;
+    let (silent2, silent_authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &silent1, &silent_authority1, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &silent2, &silent_authority2, true, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+
+    let program_id = env.program_id;
+    let oracle_key = oracle.pubkey();
+    let slash_ix = move |silent: Pubkey| {
+        with_remaining_accounts(binary_oracle::instruction::slash_no_reveal(program_id, oracle_key), &[silent])
+    };
+    let ix = slash_ix(silent1);
+    env.send(&[ix], &[]).await.unwrap();
+    let slashed = env.node(silent1).await;
+    assert_eq!(slashed.slashed_amount, collateral);
+    assert_eq!(slashed.slash_reward, 0);
+    assert_eq!(slashed.slash_reason, Some(SlashReason::NoReveal));
+
+    // The second silent node would take the round past max_slash_per_round
+    let ix = slash_ix(silent2);
+    let result = env.send(&[ix], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::SlashCapReached);
+
+    let ix = binary_oracle::instruction::overturn_slash(env.program_id, oracle.pubkey(), silent1, authority.pubkey());
+    env.send(&[ix], &[&authority]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.slashed_pool, 0);
+    assert_eq!(state.slashed_this_round, 0);
+    assert_eq!(state.slashed_nodes, 0);
+    let reinstated = env.node(silent1).await;
+    assert_eq!(reinstated.state, NodeState::Committed);
+    assert_eq!(reinstated.slashed_amount, 0);
+    assert_eq!(reinstated.slash_reason, None);
+}
+
+#[tokio::test]
+async fn test_declare_abstain_rejects_node_from_other_oracle() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle_a = env.initialize(&authority, 1_000_000, 3600, 2).await;
+    let authority_b = Keypair::new();
+    let oracle_b = env.initialize(&authority_b, 1_000_000, 3600, 2).await;
+    let (node, node_authority) = env.join(oracle_a.pubkey()).await;
+    env.join(oracle_b.pubkey()).await;
+    env.start_request(oracle_a.pubkey(), &authority).await;
+    // Both oracles are in Commit, so only the account constraints stand in the way
+    env.start_request(oracle_b.pubkey(), &authority_b).await;
+
+    let ix = binary_oracle::instruction::declare_abstain(
+        env.program_id,
+        oracle_b.pubkey(),
+        node,
+        node_authority.pubkey(),
+    );
+    assert!(env.send(&[ix], &[&node_authority]).await.is_err());
+    assert_eq!(env.oracle(oracle_b.pubkey()).await.total_nodes, 1);
+}
+
+#[tokio::test]
+async fn test_abstainer_cannot_reveal_and_gets_stake_back_on_close() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 2).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    let (idle, idle_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    let ix = binary_oracle::instruction::declare_abstain(
+        env.program_id,
+        oracle.pubkey(),
+        idle,
+        idle_authority.pubkey(),
+    );
+    env.send(&[ix], &[&idle_authority]).await.unwrap();
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    let result = env.reveal(oracle.pubkey(), &idle, &idle_authority, true, [2u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::NodeAbstained);
+
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    // The abstainer is left out of resolve, so its stake is still on the oracle
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+
+    let rent = env.lamports(idle).await;
+    let before = env.lamports(idle_authority.pubkey()).await;
+    let close_ix = binary_oracle::instruction::close_node(env.program_id, oracle.pubkey(), idle, idle_authority.pubkey());
+    env.send(&[close_ix], &[&idle_authority]).await.unwrap();
+    assert_eq!(env.lamports(idle_authority.pubkey()).await, before + rent + collateral);
+}
+
+#[tokio::test]
+async fn test_claim_abandoned_refunds_abstainer() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let commit_deadline = 600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                commit_deadline,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    let (idle, idle_authority) = env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    let ix = binary_oracle::instruction::declare_abstain(
+        env.program_id,
+        oracle.pubkey(),
+        idle,
+        idle_authority.pubkey(),
+    );
+    env.send(&[ix], &[&idle_authority]).await.unwrap();
+
+    env.set_time(commit_deadline).await;
+    let cancel_ix = binary_oracle::instruction::cancel_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    env.send(&[cancel_ix], &[&authority]).await.unwrap();
+
+    let before = env.lamports(idle_authority.pubkey()).await;
+    let ix = with_remaining_accounts(
+        binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), idle_authority.pubkey()),
+        &[idle, idle_authority.pubkey()],
+    );
+    env.send(&[ix], &[&idle_authority]).await.unwrap();
+    assert_eq!(env.lamports(idle_authority.pubkey()).await, before + collateral);
+    assert_eq!(env.node(idle).await.state, NodeState::Withdrawn);
+}
+
+#[tokio::test]
+async fn test_slash_colluding_rejects_abstainer() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 3).await;
+    let (idle, idle_authority) = env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    let ix = binary_oracle::instruction::declare_abstain(
+        env.program_id,
+        oracle.pubkey(),
+        idle,
+        idle_authority.pubkey(),
+    );
+    env.send(&[ix], &[&idle_authority]).await.unwrap();
+
+    // An abstainer has no commitment to leak, so there is nothing to prove against it
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    let result = env.slash(oracle.pubkey(), idle, &slasher, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::NotCommitted);
+    assert_eq!(env.node(idle).await.state, NodeState::Abstained);
+}
+
+// Oracle posting collateral in a fresh 6-decimal mint standing in for USDC, with `node_count`
+// nodes joined through the vault. Returns (oracle, authority, mint, vault, [(node, authority, token account)])
+async fn token_oracle(
+    env: &mut TestEnv,
+    config: OracleConfig,
+    node_count: usize,
+) -> (Keypair, Keypair, Pubkey, Pubkey, Vec<(Pubkey, Keypair, Pubkey)>) {
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = config.collateral;
+    let oracle = env.initialize_with(&authority, config).await.unwrap();
+
+    let mint = Keypair::new();
+    let rent = env.banks_client.get_rent().await.unwrap();
+    let create_mint = [
+        system_instruction::create_account(
+            &env.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &authority.pubkey(), None, 6).unwrap(),
+    ];
+    env.send(&create_mint, &[&mint]).await.unwrap();
+
+    let vault_authority = Pubkey::find_program_address(&[b"vault", oracle.pubkey().as_ref()], &env.program_id).0;
+    let vault = get_associated_token_address(&vault_authority, &mint.pubkey());
+    let ix = binary_oracle::instruction::init_token_vault(
+        env.program_id,
+        oracle.pubkey(),
+        mint.pubkey(),
+        vault_authority,
+        vault,
+        authority.pubkey(),
+    );
+    env.send(&[ix], &[&authority]).await.unwrap();
+
+    let mut nodes = Vec::new();
+    for _ in 0..node_count {
+        let node_authority = env.funded_keypair(1_000_000_000).await;
+        let node = node_address(&env.program_id, &oracle.pubkey(), &node_authority.pubkey());
+        let owner_token = get_associated_token_address(&node_authority.pubkey(), &mint.pubkey());
+        let fund = [
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &env.payer.pubkey(),
+                &node_authority.pubkey(),
+                &mint.pubkey(),
+                &spl_token::id(),
+            ),
+            spl_token::instruction::mint_to(&spl_token::id(), &mint.pubkey(), &owner_token, &authority.pubkey(), &[], collateral)
+                .unwrap(),
+        ];
+        env.send(&fund, &[&authority]).await.unwrap();
+
+        let ix = with_token_accounts(
+            binary_oracle::instruction::join_network(
+                env.program_id,
+                oracle.pubkey(),
+                node,
+                membership_address(&env.program_id, &oracle.pubkey(), &node_authority.pubkey()),
+                node_authority.pubkey(),
+                collateral,
+            ),
+            &env.program_id,
+            &oracle.pubkey(),
+            owner_token,
+            vault,
+        );
+        env.send(&[ix], &[&node_authority]).await.unwrap();
+        nodes.push((node, node_authority, owner_token));
+    }
+    (oracle, authority, mint.pubkey(), vault, nodes)
+}
+
+fn token_balance(account: solana_sdk::account::Account) -> u64 {
+    TokenAccount::try_deserialize(&mut account.data.as_slice()).unwrap().amount
+}
+
+#[tokio::test]
+async fn test_token_collateral_moves_through_vault() {
+    let mut env = TestEnv::new().await;
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let (oracle, authority, _, vault, nodes) = token_oracle(&mut env, config(collateral, reveal_duration, 2), 2).await;
+
+    let vault_account = env.banks_client.get_account(vault).await.unwrap().unwrap();
+    assert_eq!(token_balance(vault_account), 2 * collateral);
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority, _)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority, _)) in nodes.iter().enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+    env.set_time(reveal_duration + 1).await;
+    let keys: Vec<Pubkey> = nodes.iter().map(|(node, _, _)| *node).collect();
+    env.resolve(oracle.pubkey(), &authority, &keys).await.unwrap();
+
+    // Resolve leaves tokens in the vault; each node pulls its share with reclaim_collateral
+    let vault_account = env.banks_client.get_account(vault).await.unwrap().unwrap();
+    assert_eq!(token_balance(vault_account), 2 * collateral);
+
+    let (node, node_authority, owner_token) = &nodes[0];
+    let ix = with_token_accounts(
+        binary_oracle::instruction::reclaim_collateral(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+        ),
+        &env.program_id,
+        &oracle.pubkey(),
+        *owner_token,
+        vault,
+    );
+    env.send(&[ix], &[node_authority]).await.unwrap();
+
+    let owner_account = env.banks_client.get_account(*owner_token).await.unwrap().unwrap();
+    assert_eq!(token_balance(owner_account), collateral);
+    let vault_account = env.banks_client.get_account(vault).await.unwrap().unwrap();
+    assert_eq!(token_balance(vault_account), collateral);
+}
+
+#[tokio::test]
+async fn test_token_slash_pays_slasher_and_remainder_from_vault() {
+    let mut env = TestEnv::new().await;
+    let collateral = 1_000_000;
+    let commit_deadline = 600;
+    let slash_config = OracleConfig {
+        commit_deadline,
+        slash_bps: 5_000,
+        slasher_reward_bps: 1_000,
+        ..config(collateral, 3600, 2)
+    };
+    env.set_time(0).await;
+    let (oracle, authority, mint, vault, nodes) = token_oracle(&mut env, slash_config, 2).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    let (node, node_authority, owner_token) = &nodes[0];
+    env.commit(oracle.pubkey(), node, node_authority, true, [1u8; 32]).await;
+
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    let slasher_token = get_associated_token_address(&slasher.pubkey(), &mint);
+    let create_ix = spl_associated_token_account::instruction::create_associated_token_account(
+        &env.payer.pubkey(),
+        &slasher.pubkey(),
+        &mint,
+        &spl_token::id(),
+    );
+    env.send(&[create_ix], &[]).await.unwrap();
+    let slash_ix = with_token_accounts(
+        binary_oracle::instruction::slash_colluding(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            slasher.pubkey(),
+            true,
+            [1u8; 32],
+        ),
+        &env.program_id,
+        &oracle.pubkey(),
+        slasher_token,
+        vault,
+    );
+    let oracle_lamports = env.lamports(oracle.pubkey()).await;
+    env.send(&[slash_ix], &[&slasher]).await.unwrap();
+
+    // Half the stake is seized and a tenth of that goes to the slasher, in tokens
+    let seized = collateral / 2;
+    let slasher_account = env.banks_client.get_account(slasher_token).await.unwrap().unwrap();
+    assert_eq!(token_balance(slasher_account), seized / 10);
+    assert_eq!(env.lamports(oracle.pubkey()).await, oracle_lamports);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, seized - seized / 10);
+
+    // Once the request is cancelled the node reclaims the unseized half from the vault
+    env.set_time(commit_deadline).await;
+    let cancel_ix = binary_oracle::instruction::cancel_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    env.send(&[cancel_ix], &[&authority]).await.unwrap();
+    let reclaim_ix = with_token_accounts(
+        binary_oracle::instruction::reclaim_collateral(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+        ),
+        &env.program_id,
+        &oracle.pubkey(),
+        *owner_token,
+        vault,
+    );
+    env.send(&[reclaim_ix], &[node_authority]).await.unwrap();
+    let owner_account = env.banks_client.get_account(*owner_token).await.unwrap().unwrap();
+    assert_eq!(token_balance(owner_account), collateral - seized);
+}
+
+#[tokio::test]
+async fn test_token_stakes_refund_from_vault_on_leave_and_cancel() {
+    let mut env = TestEnv::new().await;
+    let collateral = 1_000_000;
+    let commit_deadline = 600;
+    env.set_time(0).await;
+    let (oracle, authority, _, vault, nodes) = token_oracle(
+        &mut env,
+        OracleConfig {
+            commit_deadline,
+            ..config(collateral, 3600, 3)
+        },
+        3,
+    )
+    .await;
+
+    // A node leaving before it commits gets its tokens straight back from the vault
+    let (leaver, leaver_authority, leaver_token) = &nodes[2];
+    let ix = with_token_accounts(
+        binary_oracle::instruction::leave_network(
+            env.program_id,
+            oracle.pubkey(),
+            *leaver,
+            membership_address(&env.program_id, &oracle.pubkey(), &leaver_authority.pubkey()),
+            leaver_authority.pubkey(),
+        ),
+        &env.program_id,
+        &oracle.pubkey(),
+        *leaver_token,
+        vault,
+    );
+    env.send(&[ix], &[leaver_authority]).await.unwrap();
+    let leaver_account = env.banks_client.get_account(*leaver_token).await.unwrap().unwrap();
+    assert_eq!(token_balance(leaver_account), collateral);
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    let (node1, authority1, _) = &nodes[0];
+    env.commit(oracle.pubkey(), node1, authority1, true, [1u8; 32]).await;
+    env.set_time(commit_deadline).await;
+    let cancel_ix = binary_oracle::instruction::cancel_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    env.send(&[cancel_ix], &[&authority]).await.unwrap();
+
+    // The vault accounts lead, then each node is paired with its authority's token account
+    let vault_authority = Pubkey::find_program_address(&[b"vault", oracle.pubkey().as_ref()], &env.program_id).0;
+    let mut accounts = vec![
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(vault_authority, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    for (node, _, owner_token) in nodes[..2].iter() {
+        accounts.push(AccountMeta::new(*node, false));
+        accounts.push(AccountMeta::new(*owner_token, false));
+    }
+    let mut claim_ix = binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), authority1.pubkey());
+    claim_ix.accounts.extend(accounts);
+    env.send(&[claim_ix], &[authority1]).await.unwrap();
+
+    for (node, _, owner_token) in nodes[..2].iter() {
+        let owner_account = env.banks_client.get_account(*owner_token).await.unwrap().unwrap();
+        assert_eq!(token_balance(owner_account), collateral);
+        assert_eq!(env.node(*node).await.state, NodeState::Withdrawn);
+    }
+    let vault_account = env.banks_client.get_account(vault).await.unwrap().unwrap();
+    assert_eq!(token_balance(vault_account), 0);
+}
+
+#[tokio::test]
+async fn test_cancel_request_after_commit_deadline_refunds_nodes() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let commit_deadline = 600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                commit_deadline,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+
+    let cancel_ix = binary_oracle::instruction::cancel_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    let result = env.send(&[cancel_ix.clone()], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::CommitDeadlineNotReached);
+
+    env.set_time(commit_deadline).await;
+    env.send(&[cancel_ix], &[&authority]).await.unwrap();
+    assert!(env.oracle(oracle.pubkey()).await.phase == Phase::Cancelled);
+
+    // Committed or not, every node gets its full collateral back
+    for (node, node_authority) in [(&node1, &authority1), (&node2, &authority2)] {
+        let before = env.lamports(node_authority.pubkey()).await;
+        let ix = with_remaining_accounts(
+            binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), node_authority.pubkey()),
+            &[*node, node_authority.pubkey()],
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+        assert_eq!(env.lamports(node_authority.pubkey()).await, before + collateral);
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_request_rejected_once_reveal_started() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                commit_deadline: 600,
+                ..config(1_000_000, 3600, 1)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    env.set_time(600).await;
+    let cancel_ix = binary_oracle::instruction::cancel_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    assert_error(env.send(&[cancel_ix], &[&authority]).await, binary_oracle::ErrorCode::InvalidPhase);
+}
+
+#[tokio::test]
+async fn test_all_committed_starts_reveal_before_commit_end() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                commit_duration: 600,
+                ..config(1_000_000, 3600, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    assert_eq!(env.oracle(oracle.pubkey()).await.commit_end_time, 600);
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    assert!(env.oracle(oracle.pubkey()).await.phase == Phase::Reveal);
+
+    env.set_time(601).await;
+    let ix = binary_oracle::instruction::close_commit(env.program_id, oracle.pubkey());
+    assert_error(env.send(&[ix], &[]).await, binary_oracle::ErrorCode::InvalidPhase);
+}
+
+#[tokio::test]
+async fn test_close_commit_forces_reveal_with_committed_subset() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                commit_duration: 600,
+                allow_minority_resolve: true,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (absent, _) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+
+    let ix = binary_oracle::instruction::close_commit(env.program_id, oracle.pubkey());
+    let result = env.send(&[ix.clone()], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::CommitPhaseNotClosed);
+
+    env.set_time(601).await;
+    env.send(&[ix], &[]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Reveal);
+    assert_eq!(state.reveal_end_time, 601 + reveal_duration);
+
+    // The node that never committed gets nothing at resolve
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    let absent_before = env.lamports(absent).await;
+    env.set_time(601 + reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, absent]).await.unwrap();
+    assert_eq!(env.lamports(absent).await, absent_before);
+    assert!(env.node(node1).await.rewarded);
+}
+
+#[tokio::test]
+async fn test_authority_joins_each_oracle_once() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 3).await;
+    let other = env.initialize(&authority, 1_000_000, 3600, 3).await;
+
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    assert_eq!(node, node_address(&env.program_id, &oracle.pubkey(), &node_authority.pubkey()));
+
+    // The node address is derived from the authority, so a second join collides with the first
+    assert!(env.try_join(oracle.pubkey(), &node_authority).await.is_err());
+    assert_eq!(env.oracle(oracle.pubkey()).await.total_nodes, 1);
+
+    // The same authority can still join a different oracle
+    env.try_join(other.pubkey(), &node_authority).await.unwrap();
+    assert_eq!(env.oracle(other.pubkey()).await.total_nodes, 1);
+}
+
+#[tokio::test]
+async fn test_node_cannot_vote_in_another_oracle() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle_a = env.initialize(&authority, 1_000_000, reveal_duration, 3).await;
+    let oracle_b = env.initialize(&authority, 1_000_000, reveal_duration, 3).await;
+
+    let (node_a, authority_a) = env.join(oracle_a.pubkey()).await;
+    let (node_b, authority_b) = env.join(oracle_b.pubkey()).await;
+    env.start_request(oracle_a.pubkey(), &authority).await;
+    env.start_request(oracle_b.pubkey(), &authority).await;
+    env.set_time(0).await;
+
+    // A's node can't commit into B
+    let result = env.try_commit(oracle_b.pubkey(), &node_a, &authority_a, true, [9u8; 32]).await;
+    assert!(result.is_err());
+
+    env.commit(oracle_a.pubkey(), &node_a, &authority_a, true, [1u8; 32]).await;
+    env.commit(oracle_b.pubkey(), &node_b, &authority_b, false, [2u8; 32]).await;
+    env.reveal(oracle_a.pubkey(), &node_a, &authority_a, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle_b.pubkey(), &node_b, &authority_b, false, [2u8; 32]).await.unwrap();
+
+    // Nor can it be counted in B's resolution
+    env.set_time(reveal_duration + 1).await;
+    let result = env.resolve(oracle_b.pubkey(), &authority, &[node_b, node_a]).await;
+    assert_error(result, binary_oracle::ErrorCode::NodeOracleMismatch);
+
+    env.resolve(oracle_b.pubkey(), &authority, &[node_b]).await.unwrap();
+    assert!(!env.node(node_a).await.rewarded);
+}
+
+#[tokio::test]
+async fn test_resolve_rejects_fabricated_node_account() {
+    // A Node-shaped account claiming a consensus vote, but owned by another program
+    let fake_node = Pubkey::new_unique();
+    let mut data = Vec::new();
+    Node {
+        authority: Pubkey::new_unique(),
+        oracle: Pubkey::default(),
+        vote_hash: Some([1u8; 32]),
+        vote: Some(1),
+        state: NodeState::Revealed,
+        stake: 1_000_000,
+        rewarded: false,
+        sealed_vote: None,
+        slashed_by: None,
+        reputation_recorded: false,
+        slash_witnesses: Vec::new(),
+        claimed: false,
+        committed_at: 0,
+        revealed_at: 0,
+        operator_recorded: false,
+        slashed_amount: 0,
+        slash_reward: 0,
+        slash_reason: None,
+        pre_slash_state: None,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    let account = solana_sdk::account::Account {
+        lamports: 10_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    };
+
+    let mut env = TestEnv::with_accounts(vec![(fake_node, account)]).await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 3).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+
+    env.set_time(reveal_duration + 1).await;
+    let result = env.resolve(oracle.pubkey(), &authority, &[node, fake_node]).await;
+    assert_error(result, binary_oracle::ErrorCode::ForeignNodeAccount);
+    assert!(!env.oracle(oracle.pubkey()).await.is_resolved);
+
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_three_way_vote_resolves_to_plurality() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                outcome_count: 3,
+                ..config(collateral, reveal_duration, 4)
+            },
+        )
+        .await
+        .unwrap();
+
+    let outcomes = [2u8, 0, 2, 1];
+    let mut nodes = Vec::new();
+    for _ in outcomes.iter() {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, ((node, node_authority), outcome)) in nodes.iter().zip(outcomes).enumerate() {
+        let ix = binary_oracle::instruction::commit(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+            outcome_hash(&oracle.pubkey(), node, outcome, &[i as u8; 32]),
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+    }
+
+    // Binary reveals are rejected, as are outcomes past outcome_count
+    let (node, node_authority) = &nodes[1];
+    let result = env.reveal(oracle.pubkey(), node, node_authority, false, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::WrongRevealMode);
+    let ix = binary_oracle::instruction::reveal_outcome(
+        env.program_id,
+        oracle.pubkey(),
+        *node,
+        node_authority.pubkey(),
+        3,
+        [1u8; 32],
+    );
+    assert_error(env.send(&[ix], &[node_authority]).await, binary_oracle::ErrorCode::InvalidReveal);
+
+    for (i, ((node, node_authority), outcome)) in nodes.iter().zip(outcomes).enumerate() {
+        let ix = binary_oracle::instruction::reveal_outcome(
+            env.program_id,
+            oracle.pubkey(),
+            *node,
+            node_authority.pubkey(),
+            outcome,
+            [i as u8; 32],
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+        assert_eq!(env.node(*node).await.vote, Some(outcome as u64));
+    }
+
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    let mut before = Vec::new();
+    for node in node_keys.iter() {
+        before.push(env.lamports(*node).await);
+    }
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &node_keys).await.unwrap();
+
+    // Outcome 2 has two of the four reveals; only its voters are paid
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.resolution_outcome, 2);
+    assert_eq!(env.lamports(node_keys[0]).await - before[0], collateral);
+    assert_eq!(env.lamports(node_keys[2]).await - before[2], collateral);
+    assert_eq!(env.lamports(node_keys[1]).await, before[1]);
+    assert_eq!(env.lamports(node_keys[3]).await, before[3]);
+}
+
+#[tokio::test]
+async fn test_large_stake_outvotes_two_small_stakes() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                stake_weighted: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    // Stake below the oracle's collateral is rejected
+    let whale = env.funded_keypair(10_000_000_000).await;
+    let result = env.try_join_with_stake(oracle.pubkey(), &whale, collateral - 1).await;
+    assert_error(result, binary_oracle::ErrorCode::InsufficientStake);
+
+    let whale_stake = 5 * collateral;
+    env.try_join_with_stake(oracle.pubkey(), &whale, whale_stake).await.unwrap();
+    let whale_node = node_address(&env.program_id, &oracle.pubkey(), &whale.pubkey());
+    assert_eq!(env.node(whale_node).await.stake, whale_stake);
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &whale_node, &whale, false, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [2u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [3u8; 32]).await;
+    env.reveal(oracle.pubkey(), &whale_node, &whale, false, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [2u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [3u8; 32]).await.unwrap();
+
+    let before = env.lamports(whale_node).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[whale_node, node1, node2]).await.unwrap();
+
+    // Two votes against one, but 2x collateral of weight against 5x
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(!state.resolution_bit);
+    assert_eq!(state.consensus_stake, whale_stake);
+    assert_eq!(env.lamports(whale_node).await - before, whale_stake);
+    assert!(!env.node(node1).await.rewarded);
+}
+
+#[tokio::test]
+async fn test_stake_weighted_rewards_split_slashed_pool_by_stake() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                stake_weighted: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let large = env.funded_keypair(10_000_000_000).await;
+    env.try_join_with_stake(oracle.pubkey(), &large, 3 * collateral).await.unwrap();
+    let large_node = node_address(&env.program_id, &oracle.pubkey(), &large.pubkey());
+    let (small_node, small) = env.join(oracle.pubkey()).await;
+    let (colluder_node, colluder) = env.join(oracle.pubkey()).await;
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &colluder_node, &colluder, false, [9u8; 32]).await;
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), colluder_node, &slasher, false, [9u8; 32]).await.unwrap();
+    env.commit(oracle.pubkey(), &large_node, &large, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &small_node, &small, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &large_node, &large, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &small_node, &small, true, [2u8; 32]).await.unwrap();
+
+    let large_before = env.lamports(large_node).await;
+    let small_before = env.lamports(small_node).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[large_node, small_node]).await.unwrap();
+
+    // The slashed collateral is split 3:1 between the two consensus nodes
+    assert_eq!(env.lamports(large_node).await - large_before, 3 * collateral + collateral * 3 / 4);
+    assert_eq!(env.lamports(small_node).await - small_before, collateral + collateral / 4);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, 0);
+}
+
+#[tokio::test]
+async fn test_vote_and_resolve_events() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 2).await;
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+
+    let start_ix = binary_oracle::instruction::start_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    let events: Vec<RequestStarted> = env.send_with_events(&[start_ix], &[&authority]).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].oracle, oracle.pubkey());
+    assert_eq!(events[0].total_nodes, 2);
+
+    env.set_time(0).await;
+    let commit_ix = binary_oracle::instruction::commit(
+        env.program_id,
+        oracle.pubkey(),
+        node1,
+        authority1.pubkey(),
+        vote_hash(&oracle.pubkey(), &node1, true, &[1u8; 32]),
+    );
+    let events: Vec<VoteCommitted> = env.send_with_events(&[commit_ix], &[&authority1]).await;
+    assert_eq!((events[0].oracle, events[0].node), (oracle.pubkey(), node1));
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+
+    let reveal_ix = binary_oracle::instruction::reveal(
+        env.program_id,
+        oracle.pubkey(),
+        node1,
+        authority1.pubkey(),
+        true,
+        [1u8; 32],
+    );
+    let events: Vec<VoteRevealed> = env.send_with_events(&[reveal_ix], &[&authority1]).await;
+    assert_eq!((events[0].node, events[0].vote), (node1, true));
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+
+    env.set_time(reveal_duration + 1).await;
+    let resolve_ix = with_remaining_accounts(
+        binary_oracle::instruction::resolve(env.program_id, oracle.pubkey(), authority.pubkey(), authority.pubkey()),
+        &[node1, node2],
+    );
+    let events: Vec<OracleResolved> = env.send_with_events(&[resolve_ix], &[&authority]).await;
+    assert_eq!(events.len(), 1);
+    let resolved = &events[0];
+    assert_eq!(resolved.oracle, oracle.pubkey());
+    assert_eq!((resolved.true_votes, resolved.false_votes), (2, 0));
+    assert_eq!(resolved.resolution_bit, env.oracle(oracle.pubkey()).await.resolution_bit);
+}
+
+#[tokio::test]
+async fn test_oracle_status_counts_reveals() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 3).await;
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes.iter().enumerate().take(2) {
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+
+    let ix = binary_oracle::instruction::oracle_status(env.program_id, oracle.pubkey());
+    let status: OracleStatus = env.simulate(ix, &[]).await;
+    assert!(status.phase == Phase::Reveal);
+    assert_eq!(status.total_nodes, 3);
+    assert_eq!(status.committed_nodes, 3);
+    assert_eq!(status.revealed_nodes, 2);
+    assert_eq!(status.reveal_end_time, env.oracle(oracle.pubkey()).await.reveal_end_time);
+    assert!(!status.is_resolved);
+}
+
+// Runs a round where `revealers` of three committed nodes reveal, then resolves
+async fn resolve_with_reveals(revealers: usize, allow_minority_resolve: bool) -> Result<(), BanksClientError> {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                allow_minority_resolve,
+                ..config(1_000_000, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes.iter().enumerate().take(revealers) {
+        env.reveal(oracle.pubkey(), node, node_authority, true, [i as u8; 32]).await.unwrap();
+    }
+    assert_eq!(env.oracle(oracle.pubkey()).await.revealed_nodes, revealers as u64);
+
+    env.set_time(reveal_duration + 1).await;
+    let keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    env.resolve(oracle.pubkey(), &authority, &keys).await
+}
+
+#[tokio::test]
+async fn test_resolve_with_majority_participation() {
+    resolve_with_reveals(2, false).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_resolve_rejects_minority_participation_by_default() {
+    let result = resolve_with_reveals(1, false).await;
+    assert_error(result, binary_oracle::ErrorCode::InsufficientParticipation);
+}
+
+#[tokio::test]
+async fn test_minority_resolve_when_allowed() {
+    resolve_with_reveals(1, true).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_start_request_waits_for_min_nodes() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+
+    // A minimum above the committee size could never be met
+    let result = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_nodes: 4,
+                ..config(1_000_000, 3600, 3)
+            },
+        )
+        .await
+        .map(|_| ());
+    assert_error(result, binary_oracle::ErrorCode::InvalidMinNodes);
+
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                min_nodes: 2,
+                ..config(1_000_000, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    env.join(oracle.pubkey()).await;
+    let ix = binary_oracle::instruction::start_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    let result = env.send(&[ix.clone()], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::NotEnoughNodes);
+
+    env.join(oracle.pubkey()).await;
+    env.send(&[ix], &[&authority]).await.unwrap();
+    assert!(env.oracle(oracle.pubkey()).await.phase == Phase::Commit);
+}
+
+#[tokio::test]
+async fn test_extend_reveal_bounded_by_deadline_and_count() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                max_reveal_extensions: 2,
+                ..config(1_000_000, reveal_duration, 1)
+            },
+        )
+        .await
+        .unwrap();
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    let extend = |seconds: i64| {
+        binary_oracle::instruction::extend_reveal(env.program_id, oracle.pubkey(), authority.pubkey(), seconds)
+    };
+    let (first, second, third) = (extend(600), extend(300), extend(100));
+
+    // Only the authority may extend
+    let outsider = env.funded_keypair(1_000_000_000).await;
+    let ix = binary_oracle::instruction::extend_reveal(env.program_id, oracle.pubkey(), outsider.pubkey(), 600);
+    assert!(env.send(&[ix], &[&outsider]).await.is_err());
+
+    env.send(&[first], &[&authority]).await.unwrap();
+    assert_eq!(env.oracle(oracle.pubkey()).await.reveal_end_time, reveal_duration + 600);
+
+    // Past the original deadline but within the extension
+    env.set_time(reveal_duration + 1).await;
+    env.send(&[second], &[&authority]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.reveal_end_time, reveal_duration + 900);
+    assert_eq!(state.extensions_used, 2);
+
+    let result = env.send(&[third.clone()], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::MaxExtensionsReached);
+
+    // A node that missed the original deadline can still reveal
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+
+    env.set_time(reveal_duration + 901).await;
+    let result = env.send(&[third], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealPhaseClosed);
+}
+
+#[tokio::test]
+async fn test_extend_reveal_capped_in_total() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                max_reveal_extensions: 3,
+                ..config(1_000_000, reveal_duration, 1)
+            },
+        )
+        .await
+        .unwrap();
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    let program_id = env.program_id;
+    let extend = |seconds: i64| {
+        binary_oracle::instruction::extend_reveal(program_id, oracle.pubkey(), authority.pubkey(), seconds)
+    };
+    let pause_ix = |paused| binary_oracle::instruction::set_paused(program_id, oracle.pubkey(), authority.pubkey(), paused);
+
+    // A single extension past the cap is rejected outright
+    let result = env.send(&[extend(MAX_REVEAL_EXTENSION + 1)], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealExtensionCapReached);
+
+    env.send(&[extend(MAX_REVEAL_EXTENSION - 600)], &[&authority]).await.unwrap();
+    env.send(&[extend(600)], &[&authority]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.reveal_end_time, reveal_duration + MAX_REVEAL_EXTENSION);
+
+    // Extensions left in the count don't help once the total is used up
+    let result = env.send(&[extend(1)], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealExtensionCapReached);
+
+    // Time spent paused no longer moves the deadline either
+    env.send(&[pause_ix(true)], &[&authority]).await.unwrap();
+    env.set_time(1000).await;
+    env.send(&[pause_ix(false)], &[&authority]).await.unwrap();
+    assert_eq!(env.oracle(oracle.pubkey()).await.reveal_end_time, reveal_duration + MAX_REVEAL_EXTENSION);
+}
+
+#[tokio::test]
+async fn test_node_cannot_be_slashed_twice() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env.initialize(&authority, collateral, 3600, 3).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), node, &slasher, true, [1u8; 32]).await.unwrap();
+    let oracle_before = env.lamports(oracle.pubkey()).await;
+
+    // A different slasher replaying the same proof gets nothing more out of the node
+    let second_slasher = env.funded_keypair(1_000_000_000).await;
+    let result = env.slash(oracle.pubkey(), node, &second_slasher, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadySlashed);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, collateral);
+    assert_eq!(env.lamports(oracle.pubkey()).await, oracle_before);
+}
+
+#[tokio::test]
+async fn test_slasher_paid_cut_of_slashed_collateral() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                slasher_reward_bps: 2_000,
+                ..config(collateral, 3600, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    let slasher_before = env.lamports(slasher.pubkey()).await;
+    let oracle_before = env.lamports(oracle.pubkey()).await;
+    env.slash(oracle.pubkey(), node, &slasher, true, [1u8; 32]).await.unwrap();
+
+    // 20% goes to the slasher, the rest waits in the pool for consensus nodes
+    let cut = collateral / 5;
+    assert_eq!(env.lamports(slasher.pubkey()).await - slasher_before, cut);
+    assert_eq!(oracle_before - env.lamports(oracle.pubkey()).await, cut);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, collateral - cut);
+}
+
+#[test]
+fn test_reward_math_checks_for_overflow() {
+    let overflow: anchor_lang::error::Error = binary_oracle::ErrorCode::ArithmeticOverflow.into();
+
+    // reward * bps overflows a u64 long before the divide; the split still comes out exact
+    let reward = u64::MAX - 1;
+    let (net, rebate) = split_loser_rebate(reward, 1, 1, 5_000).unwrap();
+    assert_eq!(rebate, reward / 2);
+    assert_eq!(net + rebate, reward);
+
+    // Many winners' deductions pooled for a single loser no longer fit in a u64
+    assert_eq!(split_loser_rebate(reward, 1_000, 1, 5_000).unwrap_err(), overflow);
+
+    // A huge collateral plus a slashed pool shared by a single node
+    assert_eq!(reward_per_node(u64::MAX, u64::MAX, 1).unwrap_err(), overflow);
+    assert_eq!(reward_per_node(u64::MAX / 2, u64::MAX / 2, 1_000).unwrap(), u64::MAX / 2 + u64::MAX / 2 / 1_000);
+    assert_eq!(apply_bps(u64::MAX, 10_000), u64::MAX);
+}
+
+// Resolves a 1-1 tie under `tie_break_policy`, returning the env and oracle for inspection
+async fn resolve_tie(tie_break_policy: TieBreakPolicy) -> (TestEnv, Keypair, Vec<(Pubkey, Keypair)>) {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                tie_break_policy,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let nodes = vec![env.join(oracle.pubkey()).await, env.join(oracle.pubkey()).await];
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, i == 0, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, i == 0, [i as u8; 32]).await.unwrap();
+    }
+    env.set_time(reveal_duration + 1).await;
+    let keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    env.resolve(oracle.pubkey(), &authority, &keys).await.unwrap();
+    (env, oracle, nodes)
+}
+
+#[tokio::test]
+async fn test_tie_without_tie_breaker_stays_unresolved() {
+    let collateral = 1_000_000;
+    let (mut env, oracle, nodes) = resolve_tie(TieBreakPolicy::default()).await;
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Tied);
+    assert!(!state.is_resolved);
+
+    // Nodes get their stake back as if the request were cancelled
+    let (node, node_authority) = &nodes[0];
+    let before = env.lamports(node_authority.pubkey()).await;
+    let ix = with_remaining_accounts(
+        binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), node_authority.pubkey()),
+        &[*node, node_authority.pubkey()],
+    );
+    env.send(&[ix], &[node_authority]).await.unwrap();
+    assert_eq!(env.lamports(node_authority.pubkey()).await - before, collateral);
+}
+
+#[tokio::test]
+async fn test_tied_refunds_skip_settled_nodes_and_share_slashed_pool() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (colluder, colluder_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &colluder, &colluder_authority, true, [9u8; 32]).await;
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), colluder, &slasher, true, [9u8; 32]).await.unwrap();
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Tied);
+    assert_eq!(state.refund_share, collateral / 2);
+
+    // node1 claims on its own first; the batch after it still goes through around node1 and the colluder
+    let claim_ix = |pairs: &[Pubkey]| {
+        with_remaining_accounts(
+            binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), authority1.pubkey()),
+            pairs,
+        )
+    };
+    let single_ix = claim_ix(&[node1, authority1.pubkey()]);
+    let batch_ix = claim_ix(&[
+        node1,
+        authority1.pubkey(),
+        colluder,
+        colluder_authority.pubkey(),
+        node2,
+        authority2.pubkey(),
+    ]);
+    let before1 = env.lamports(authority1.pubkey()).await;
+    let before2 = env.lamports(authority2.pubkey()).await;
+    env.send(&[single_ix], &[&authority1]).await.unwrap();
+    env.send(&[batch_ix], &[&authority1]).await.unwrap();
+
+    // Each revealer gets its stake plus half the colluder's forfeited collateral
+    assert_eq!(env.lamports(authority2.pubkey()).await - before2, collateral + collateral / 2);
+    assert_eq!(env.lamports(authority1.pubkey()).await - before1, collateral + collateral / 2);
+    assert_eq!(env.node(node2).await.state, NodeState::Withdrawn);
+    assert!(env.node(colluder).await.claimed);
+}
+
+#[tokio::test]
+async fn test_tie_with_tie_breaker_resolves() {
+    let (mut env, oracle, _) = resolve_tie(TieBreakPolicy::Fixed(true)).await;
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+    assert!(state.is_resolved);
+    assert!(state.resolution_bit);
+}
+
+#[tokio::test]
+async fn test_transfer_authority_needs_acceptance() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 1).await;
+    let new_authority = Keypair::new();
+
+    let transfer_ix = binary_oracle::instruction::transfer_authority(
+        env.program_id,
+        oracle.pubkey(),
+        authority.pubkey(),
+        new_authority.pubkey(),
+    );
+    env.send(&[transfer_ix], &[&authority]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.authority, authority.pubkey());
+    assert_eq!(state.pending_authority, Some(new_authority.pubkey()));
+
+    let accept_ix = binary_oracle::instruction::accept_authority(env.program_id, oracle.pubkey(), new_authority.pubkey());
+    let events: Vec<AuthorityTransferred> = env.send_with_events(&[accept_ix], &[&new_authority]).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].previous_authority, authority.pubkey());
+    assert_eq!(events[0].new_authority, new_authority.pubkey());
+
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.authority, new_authority.pubkey());
+    assert_eq!(state.pending_authority, None);
+
+    // The new authority now controls the oracle
+    env.start_request(oracle.pubkey(), &new_authority).await;
+}
+
+#[tokio::test]
+async fn test_transfer_authority_rejects_non_authority() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 1).await;
+    let intruder = Keypair::new();
+
+    let transfer_ix = binary_oracle::instruction::transfer_authority(
+        env.program_id,
+        oracle.pubkey(),
+        intruder.pubkey(),
+        intruder.pubkey(),
+    );
+    let result = env.send(&[transfer_ix], &[&intruder]).await;
+    assert_error(result, binary_oracle::ErrorCode::UnauthorizedAccess);
+
+    // Accepting without having been proposed is rejected as well
+    let accept_ix = binary_oracle::instruction::accept_authority(env.program_id, oracle.pubkey(), intruder.pubkey());
+    let result = env.send(&[accept_ix], &[&intruder]).await;
+    assert_error(result, binary_oracle::ErrorCode::UnauthorizedAccess);
+    assert_eq!(env.oracle(oracle.pubkey()).await.authority, authority.pubkey());
+}
+
+#[tokio::test]
+async fn test_paused_oracle_rejects_commits_until_resumed() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 1).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+
+    let program_id = env.program_id;
+    let pause_ix = |paused| binary_oracle::instruction::set_paused(program_id, oracle.pubkey(), authority.pubkey(), paused);
+    env.send(&[pause_ix(true)], &[&authority]).await.unwrap();
+    assert!(env.oracle(oracle.pubkey()).await.paused);
+
+    let result = env.try_commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::Paused);
+    let late_authority = env.funded_keypair(10_000_000_000).await;
+    let result = env.try_join(oracle.pubkey(), &late_authority).await;
+    assert_error(result, binary_oracle::ErrorCode::Paused);
+
+    // Only the authority may pause or resume
+    let intruder = Keypair::new();
+    let intruder_ix = binary_oracle::instruction::set_paused(env.program_id, oracle.pubkey(), intruder.pubkey(), false);
+    assert!(env.send(&[intruder_ix], &[&intruder]).await.is_err());
+
+    env.send(&[pause_ix(false)], &[&authority]).await.unwrap();
+    env.try_commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    assert!(env.oracle(oracle.pubkey()).await.phase == Phase::Reveal);
+}
+
+#[tokio::test]
+async fn test_close_node_returns_rent_after_reclaim() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 1).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+
+    let close_ix = binary_oracle::instruction::close_node(env.program_id, oracle.pubkey(), node, node_authority.pubkey());
+    let result = env.send(&[close_ix.clone()], &[&node_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidPhase);
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+
+    // A consensus node can't close before reclaiming, or its collateral would be stranded
+    let result = env.send(&[close_ix.clone()], &[&node_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::UnclaimedFunds);
+
+    let reclaim_ix = binary_oracle::instruction::reclaim_collateral(
+        env.program_id,
+        oracle.pubkey(),
+        node,
+        node_authority.pubkey(),
+    );
+    env.send(&[reclaim_ix], &[&node_authority]).await.unwrap();
+
+    let rent = env.lamports(node).await;
+    let before = env.lamports(node_authority.pubkey()).await;
+    env.send(&[close_ix], &[&node_authority]).await.unwrap();
+    assert_eq!(env.lamports(node_authority.pubkey()).await, before + rent);
+    assert!(env.banks_client.get_account(node).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_initialize_stores_question_hash() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 1).await;
+    assert_eq!(env.oracle(oracle.pubkey()).await.question_hash, question_hash(QUESTION));
+
+    let result = env.initialize_question(&authority, config(1_000_000, 3600, 1), [0u8; 32]).await;
+    assert_error(result.map(|_| ()), binary_oracle::ErrorCode::EmptyQuestionHash);
+}
+
+#[tokio::test]
+async fn test_resolve_pays_protocol_fee_from_slashed_pool() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let fee_recipient = env.funded_keypair(1_000_000_000).await;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                fee_bps: 1_000,
+                fee_recipient: fee_recipient.pubkey(),
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, true, [3u8; 32]).await;
+    env.slash(oracle.pubkey(), node3, &authority1, true, [3u8; 32]).await.unwrap();
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+
+    // The fee can only go to the configured recipient
+    let wrong_ix = with_remaining_accounts(
+        binary_oracle::instruction::resolve(env.program_id, oracle.pubkey(), authority.pubkey(), authority.pubkey()),
+        &[node1, node2],
+    );
+    let result = env.send(&[wrong_ix], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidFeeRecipient);
+
+    let recipient_before = env.lamports(fee_recipient.pubkey()).await;
+    let node1_before = env.lamports(node1).await;
+    let node2_before = env.lamports(node2).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2]).await.unwrap();
+
+    // 10% of the slashed collateral goes to the recipient; the two winners split the rest
+    let fee = collateral / 10;
+    assert_eq!(env.lamports(fee_recipient.pubkey()).await - recipient_before, fee);
+    let reward = collateral + (collateral - fee) / 2;
+    assert_eq!(env.oracle(oracle.pubkey()).await.reward_per_node, reward);
+    assert_eq!(env.lamports(node1).await - node1_before, reward);
+    assert_eq!(env.lamports(node2).await - node2_before, reward);
+}
+
+#[tokio::test]
+async fn test_batch_reveal_sets_every_vote() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 3).await;
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    let votes: Vec<(bool, [u8; 32])> = vec![(true, [1u8; 32]), (false, [2u8; 32]), (true, [3u8; 32])];
+    for ((node, node_authority), (vote, nonce)) in nodes.iter().zip(votes.iter()) {
+        env.commit(oracle.pubkey(), node, node_authority, *vote, *nonce).await;
+    }
+
+    let signers: Vec<&Keypair> = nodes.iter().map(|(_, node_authority)| node_authority).collect();
+    let program_id = env.program_id;
+    let batch_ix = |authorities_sign: bool| {
+        let mut ix =
+            binary_oracle::instruction::batch_reveal(program_id, oracle.pubkey(), signers[0].pubkey(), votes.clone());
+        for (node, node_authority) in nodes.iter() {
+            ix.accounts.push(AccountMeta::new(*node, false));
+            ix.accounts.push(AccountMeta::new_readonly(node_authority.pubkey(), authorities_sign));
+        }
+        ix
+    };
+
+    // Every node authority has to sign its part of the batch
+    let result = env.send(&[batch_ix(false)], &[signers[0]]).await;
+    assert_error(result, binary_oracle::ErrorCode::UnauthorizedAccess);
+
+    env.send(&[batch_ix(true)], &signers).await.unwrap();
+
+    for ((node, _), (vote, _)) in nodes.iter().zip(votes.iter()) {
+        let state = env.node(*node).await;
+        assert!(state.state == NodeState::Revealed);
+        assert_eq!(state.vote, Some(*vote as u64));
+    }
+    assert_eq!(env.oracle(oracle.pubkey()).await.revealed_nodes, 3);
+}
+
+#[tokio::test]
+async fn test_tally_votes_in_chunks_then_finalize_resolution() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 10).await;
+    let mut nodes = Vec::new();
+    for _ in 0..10 {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, i < 6, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, i < 6, [i as u8; 32]).await.unwrap();
+    }
+    env.set_time(reveal_duration + 1).await;
+
+    let tally_ix = binary_oracle::instruction::tally_votes(env.program_id, oracle.pubkey(), 5);
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    let finalize_ix = with_remaining_accounts(
+        binary_oracle::instruction::finalize_resolution(
+            env.program_id,
+            oracle.pubkey(),
+            authority.pubkey(),
+            authority.pubkey(),
+        ),
+        &node_keys,
+    );
+
+    // Half tallied: the totals so far are kept on the oracle and finalizing must wait
+    env.send(&[tally_ix.clone()], &[]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.tallied_nodes, 5);
+    assert_eq!((state.tally_true_votes, state.tally_false_votes), (5, 0));
+    let result = env.send(&[finalize_ix.clone()], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::TallyIncomplete);
+
+    env.send(&[tally_ix.clone()], &[]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.tallied_nodes, 10);
+    assert_eq!((state.tally_true_votes, state.tally_false_votes), (6, 4));
+
+    // The cursor stops a vote being counted twice
+    env.set_time(reveal_duration + 2).await;
+    let result = env.send(&[tally_ix], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::AllVotesTallied);
+
+    env.send(&[finalize_ix], &[&authority]).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+    assert!(state.resolution_bit);
+    assert_eq!((state.tally_true_votes, state.tally_false_votes), (6, 4));
+}
+
+#[tokio::test]
+async fn test_pull_rewards_claimed_once_by_consensus_nodes() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                pull_rewards: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, i < 2, [i as u8; 32]).await;
+    }
+    for (i, (node, node_authority)) in nodes.iter().enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, i < 2, [i as u8; 32]).await.unwrap();
+    }
+    env.set_time(reveal_duration + 1).await;
+
+    // Resolve only fixes the reward; nothing is pushed to consensus nodes
+    let mut before = Vec::new();
+    for (node, _) in nodes.iter() {
+        before.push(env.lamports(*node).await);
+    }
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    env.resolve(oracle.pubkey(), &authority, &node_keys).await.unwrap();
+    let reward = env.oracle(oracle.pubkey()).await.reward_per_node;
+    assert_eq!(reward, collateral);
+    for ((node, _), balance) in nodes.iter().take(2).zip(before.iter()) {
+        assert_eq!(env.lamports(*node).await, *balance);
+    }
+
+    let claim_ix = |env: &TestEnv, node: &Pubkey, node_authority: &Keypair| {
+        binary_oracle::instruction::claim_reward(env.program_id, oracle.pubkey(), *node, node_authority.pubkey())
+    };
+    for (node, node_authority) in nodes.iter().take(2) {
+        let authority_before = env.lamports(node_authority.pubkey()).await;
+        env.send(&[claim_ix(&env, node, node_authority)], &[node_authority]).await.unwrap();
+        assert_eq!(env.lamports(node_authority.pubkey()).await - authority_before, reward);
+        assert!(env.node(*node).await.claimed);
+
+        env.set_time(reveal_duration + 2).await;
+        let result = env.send(&[claim_ix(&env, node, node_authority)], &[node_authority]).await;
+        assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+        env.set_time(reveal_duration + 1).await;
+    }
+
+    let (loser, loser_authority) = &nodes[2];
+    let result = env.send(&[claim_ix(&env, loser, loser_authority)], &[loser_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::NotConsensusNode);
+}
+
+#[tokio::test]
+async fn test_node_records_commit_and_reveal_times() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 1).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    assert_eq!(env.node(node).await.committed_at, 0);
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(100).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    assert_eq!(env.node(node).await.committed_at, 100);
+
+    env.set_time(250).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    let state = env.node(node).await;
+    assert_eq!((state.committed_at, state.revealed_at), (100, 250));
+}
+
+#[tokio::test]
+async fn test_reveal_rejects_reveal_phase_without_deadline() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 2).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+
+    // Force the oracle into Reveal without going through start_reveal, leaving reveal_end_time at 0
+    let mut oracle_account = env.banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+    let mut state = Oracle::try_deserialize(&mut oracle_account.data.as_slice()).unwrap();
+    assert_eq!(state.reveal_end_time, 0);
+    state.phase = Phase::Reveal;
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    oracle_account.data[..data.len()].copy_from_slice(&data);
+    let node_account = env.banks_client.get_account(node).await.unwrap().unwrap();
+
+    let mut env = TestEnv::with_program_accounts(
+        env.program_id,
+        vec![(oracle.pubkey(), oracle_account), (node, node_account)],
+    )
+    .await;
+    env.set_time(0).await;
+    let result = env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealNotStarted);
+}
+
+#[tokio::test]
+async fn test_leave_network_only_before_committing() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let oracle = env.initialize(&authority, collateral, 3600, 3).await;
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.join(oracle.pubkey()).await;
+
+    let leave_ix = |env: &TestEnv, node: Pubkey, node_authority: &Keypair| {
+        binary_oracle::instruction::leave_network(
+            env.program_id,
+            oracle.pubkey(),
+            node,
+            membership_address(&env.program_id, &oracle.pubkey(), &node_authority.pubkey()),
+            node_authority.pubkey(),
+        )
+    };
+
+    // Leaving in Precommit refunds the collateral and closes the node
+    let balance = env.lamports(authority1.pubkey()).await;
+    env.send(&[leave_ix(&env, node1, &authority1)], &[&authority1]).await.unwrap();
+    assert!(env.lamports(authority1.pubkey()).await >= balance + collateral);
+    assert_eq!(env.oracle(oracle.pubkey()).await.total_nodes, 2);
+    assert!(env.banks_client.get_account(node1).await.unwrap().is_none());
+
+    // Once a node has committed it is bound to the round
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    let result = env.send(&[leave_ix(&env, node2, &authority2)], &[&authority2]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyCommitted);
+    assert_eq!(env.oracle(oracle.pubkey()).await.total_nodes, 2);
+}
+
+#[tokio::test]
+async fn test_commitment_is_bound_to_its_node() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle = env.initialize(&authority, 1_000_000, 3600, 2).await;
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+
+    // node1 copies node2's commitment instead of committing its own
+    let copied = vote_hash(&oracle.pubkey(), &node2, true, &[2u8; 32]);
+    let ix = binary_oracle::instruction::commit(env.program_id, oracle.pubkey(), node1, authority1.pubkey(), copied);
+    env.send(&[ix], &[&authority1]).await.unwrap();
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+
+    // Revealing node2's preimage from node1 fails; node2 itself reveals fine
+    let result = env.reveal(oracle.pubkey(), &node1, &authority1, true, [2u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidReveal);
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_commitment_is_bound_to_its_oracle() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let oracle_a = env.initialize(&authority, 1_000_000, 3600, 1).await;
+    let oracle_b = env.initialize(&authority, 1_000_000, 3600, 1).await;
+    let node_authority = env.funded_keypair(10_000_000_000).await;
+    env.try_join(oracle_a.pubkey(), &node_authority).await.unwrap();
+    env.try_join(oracle_b.pubkey(), &node_authority).await.unwrap();
+    let node_a = node_address(&env.program_id, &oracle_a.pubkey(), &node_authority.pubkey());
+    let node_b = node_address(&env.program_id, &oracle_b.pubkey(), &node_authority.pubkey());
+    env.start_request(oracle_a.pubkey(), &authority).await;
+    env.start_request(oracle_b.pubkey(), &authority).await;
+    env.set_time(0).await;
+
+    // The same vote and nonce, with oracle B's node committing a hash built for oracle A
+    let nonce = [7u8; 32];
+    env.commit(oracle_a.pubkey(), &node_a, &node_authority, true, nonce).await;
+    let ix = binary_oracle::instruction::commit(
+        env.program_id,
+        oracle_b.pubkey(),
+        node_b,
+        node_authority.pubkey(),
+        vote_hash(&oracle_a.pubkey(), &node_b, true, &nonce),
+    );
+    env.send(&[ix], &[&node_authority]).await.unwrap();
+
+    env.reveal(oracle_a.pubkey(), &node_a, &node_authority, true, nonce).await.unwrap();
+    let result = env.reveal(oracle_b.pubkey(), &node_b, &node_authority, true, nonce).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidReveal);
+}
+
+async fn resolve_with_quorum(votes: &[bool]) -> (TestEnv, Keypair, Vec<(Pubkey, Keypair)>) {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                quorum_bps: 6_700,
+                ..config(1_000_000, reveal_duration, votes.len() as u64)
+            },
+        )
+        .await
+        .unwrap();
+    let mut nodes = Vec::new();
+    for _ in votes {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, *vote, [i as u8; 32]).await;
+    }
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, *vote, [i as u8; 32]).await.unwrap();
+    }
+    env.set_time(reveal_duration + 1).await;
+    let keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    env.resolve(oracle.pubkey(), &authority, &keys).await.unwrap();
+    (env, oracle, nodes)
+}
+
+#[tokio::test]
+async fn test_supermajority_meets_quorum() {
+    let (mut env, oracle, _) = resolve_with_quorum(&[true, true, true]).await;
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+    assert!(state.is_resolved);
+    assert!(state.resolution_bit);
+}
+
+#[tokio::test]
+async fn test_split_below_quorum_is_inconclusive() {
+    let collateral = 1_000_000;
+    // Two of three is 66.7%, just short of the 67% quorum
+    let (mut env, oracle, nodes) = resolve_with_quorum(&[true, true, false]).await;
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Inconclusive);
+    assert!(!state.is_resolved);
+
+    // Every node, winner or loser, gets its collateral back
+    for (node, node_authority) in nodes.iter() {
+        let before = env.lamports(node_authority.pubkey()).await;
+        let ix = with_remaining_accounts(
+            binary_oracle::instruction::claim_abandoned(env.program_id, oracle.pubkey(), node_authority.pubkey()),
+            &[*node, node_authority.pubkey()],
+        );
+        env.send(&[ix], &[node_authority]).await.unwrap();
+        assert_eq!(env.lamports(node_authority.pubkey()).await - before, collateral);
+    }
+}
+
+#[tokio::test]
+async fn test_start_request_only_once() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 1).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.set_time(500).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    assert_eq!(env.oracle(oracle.pubkey()).await.request_start_time, 500);
+
+    // Restarting mid-round would reset the commit count
+    let start_ix = binary_oracle::instruction::start_request(env.program_id, oracle.pubkey(), authority.pubkey());
+    let result = env.send(&[start_ix.clone()], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::InvalidPhase);
+
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 501).await;
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+
+    let result = env.send(&[start_ix], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyResolved);
+}
+
+#[tokio::test]
+async fn test_operator_record_spans_oracles() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let operator_authority = env.funded_keypair(10_000_000_000).await;
+    let operator = operator_address(&env.program_id, &operator_authority.pubkey());
+
+    for round in 0..2 {
+        let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 1).await;
+        env.try_join(oracle.pubkey(), &operator_authority).await.unwrap();
+        let node = node_address(&env.program_id, &oracle.pubkey(), &operator_authority.pubkey());
+        env.set_time(0).await;
+        env.start_request(oracle.pubkey(), &authority).await;
+        env.commit(oracle.pubkey(), &node, &operator_authority, true, [round as u8; 32]).await;
+        env.reveal(oracle.pubkey(), &node, &operator_authority, true, [round as u8; 32]).await.unwrap();
+        env.set_time(reveal_duration + 1).await;
+        env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+
+        let record_ix = binary_oracle::instruction::record_operator(
+            env.program_id,
+            oracle.pubkey(),
+            node,
+            operator,
+            authority.pubkey(),
+        );
+        env.send(&[record_ix.clone()], &[&authority]).await.unwrap();
+
+        // Each node counts once
+        env.set_time(reveal_duration + 2).await;
+        let result = env.send(&[record_ix], &[&authority]).await;
+        assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+    }
+
+    let account = env.banks_client.get_account(operator).await.unwrap().unwrap();
+    let record = Operator::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(record.authority, operator_authority.pubkey());
+    assert_eq!(record.correct_resolutions, 2);
+    assert_eq!(record.total_participations, 2);
+    assert_eq!(record.slashed_count, 0);
+}
+
+#[tokio::test]
+async fn test_initialize_rejects_unusable_parameters() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+
+    let cases = [
+        (config(0, 3600, 3), binary_oracle::ErrorCode::InvalidCollateral),
+        (config(1_000_000, 3600, 0), binary_oracle::ErrorCode::InvalidMaxNodes),
+        (config(1_000_000, 0, 3), binary_oracle::ErrorCode::InvalidRevealDuration),
+        (config(1_000_000, -1, 3), binary_oracle::ErrorCode::InvalidRevealDuration),
+    ];
+    for (config, error) in cases {
+        let result = env.initialize_with(&authority, config).await.map(|_| ());
+        assert_error(result, error);
+    }
+
+    env.initialize_with(&authority, config(1, 1, 1)).await.unwrap();
+}
+
+#[test]
+fn test_compute_vote_hash_matches_known_vector() {
+    let oracle = Pubkey::new_from_array([1u8; 32]);
+    let node = Pubkey::new_from_array([2u8; 32]);
+    let nonce = [3u8; 32];
+
+    // sha256("binary_oracle_v1" ++ oracle ++ node ++ [1] ++ nonce)
+    let expected = [
+        29, 29, 10, 149, 118, 250, 39, 116, 182, 73, 8, 214, 164, 160, 168, 190, 221, 26, 185, 218, 72, 82, 64, 103,
+        90, 63, 169, 226, 228, 49, 76, 221,
+    ];
+    assert_eq!(compute_vote_hash(&oracle, &node, true, &nonce, None), expected);
+    assert_eq!(vote_hash(&oracle, &node, true, &nonce), expected);
+
+    // ... ++ 1000i64 little-endian, for oracles that bind the request start
+    let bound = [
+        71, 8, 66, 184, 170, 228, 207, 152, 28, 203, 195, 27, 108, 123, 183, 135, 68, 146, 255, 135, 226, 240, 252,
+        116, 248, 146, 221, 237, 85, 124, 38, 86,
+    ];
+    assert_eq!(compute_vote_hash(&oracle, &node, true, &nonce, Some(1_000)), bound);
+    assert_eq!(bound_vote_hash(&oracle, &node, true, &nonce, 1_000), bound);
+
+    assert_ne!(compute_vote_hash(&oracle, &node, false, &nonce, None), expected);
+}
+
+#[tokio::test]
+async fn test_resolve_only_once() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 1).await;
+    let (node, node_authority) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node, &node_authority, true, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node]).await.unwrap();
+    let node_balance = env.lamports(node).await;
+
+    // A second resolve must not pay anyone again
+    env.set_time(reveal_duration + 2).await;
+    let result = env.resolve(oracle.pubkey(), &authority, &[node]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyResolved);
+    assert_eq!(env.lamports(node).await, node_balance);
+    assert!(env.oracle(oracle.pubkey()).await.is_resolved);
+}
+
+#[tokio::test]
+async fn test_sequential_requests_share_a_node_set() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let reveal_duration = 3600;
+    let program_id = env.program_id;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 2).await;
+    let nodes = [env.join(oracle.pubkey()).await, env.join(oracle.pubkey()).await];
+
+    let mut now = 0;
+    for (index, answer) in [(0u64, true), (1u64, false)] {
+        env.set_time(now).await;
+        let request = request_address(&program_id, &oracle.pubkey(), index);
+        let create_ix = binary_oracle::instruction::create_request(
+            program_id,
+            oracle.pubkey(),
+            request,
+            authority.pubkey(),
+            question_hash(&format!("{QUESTION} #{index}")),
+        );
+        env.send(&[create_ix], &[&authority]).await.unwrap();
+
+        let nonce = [index as u8 + 1; 32];
+        for (node, node_authority) in nodes.iter() {
+            let ballot = ballot_address(&program_id, &request, node);
+            let commit_ix = binary_oracle::instruction::commit_request(
+                program_id,
+                oracle.pubkey(),
+                request,
+                *node,
+                ballot,
+                node_authority.pubkey(),
+                compute_vote_hash(&request, node, answer, &nonce, None),
+            );
+            env.send(&[commit_ix], &[node_authority]).await.unwrap();
+        }
+        for (node, node_authority) in nodes.iter() {
+            let ballot = ballot_address(&program_id, &request, node);
+            let reveal_ix = binary_oracle::instruction::reveal_request(
+                program_id,
+                oracle.pubkey(),
+                request,
+                *node,
+                ballot,
+                node_authority.pubkey(),
+                answer,
+                nonce,
+            );
+            env.send(&[reveal_ix], &[node_authority]).await.unwrap();
+        }
+
+        now += reveal_duration + 1;
+        env.set_time(now).await;
+        let resolve_ix = binary_oracle::instruction::resolve_request(program_id, oracle.pubkey(), request);
+        env.send(&[resolve_ix], &[]).await.unwrap();
+
+        let account = env.banks_client.get_account(request).await.unwrap().unwrap();
+        let state = Request::try_deserialize(&mut account.data.as_slice()).unwrap();
+        assert_eq!(state.index, index);
+        assert!(state.phase == Phase::Complete);
+        assert_eq!(state.resolution_bit, answer);
+        assert_eq!(state.true_votes + state.false_votes, 2);
+        now += 1;
+    }
+
+    // Both questions were answered without the nodes rejoining or the oracle's own round moving
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.request_count, 2);
+    assert_eq!(state.total_nodes, 2);
+    assert!(state.phase == Phase::Precommit);
+}
+
+#[tokio::test]
+async fn test_request_finishes_after_a_node_leaves() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 3).await;
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (leaver, leaver_authority) = env.join(oracle.pubkey()).await;
+
+    env.set_time(0).await;
+    let request = env.create_request(oracle.pubkey(), &authority).await;
+    env.commit_request(oracle.pubkey(), request, &node1, &authority1, true, [1u8; 32]).await;
+    env.commit_request(oracle.pubkey(), request, &node2, &authority2, true, [2u8; 32]).await;
+
+    // Still waiting on the third node, so the reveal window can't be forced open yet
+    let close_ix = binary_oracle::instruction::close_request_commit(env.program_id, oracle.pubkey(), request);
+    let result = env.send(&[close_ix.clone()], &[]).await;
+    assert_error(result, binary_oracle::ErrorCode::CommitPhaseNotClosed);
+
+    let leave_ix = binary_oracle::instruction::leave_network(
+        env.program_id,
+        oracle.pubkey(),
+        leaver,
+        membership_address(&env.program_id, &oracle.pubkey(), &leaver_authority.pubkey()),
+        leaver_authority.pubkey(),
+    );
+    env.send(&[leave_ix], &[&leaver_authority]).await.unwrap();
+
+    // With the leaver gone everyone left has committed
+    env.send(&[close_ix], &[]).await.unwrap();
+    assert!(env.request(request).await.phase == Phase::Reveal);
+    env.reveal_request(oracle.pubkey(), request, &node1, &authority1, true, [1u8; 32]).await;
+    env.reveal_request(oracle.pubkey(), request, &node2, &authority2, true, [2u8; 32]).await;
+
+    env.set_time(reveal_duration + 1).await;
+    let resolve_ix = binary_oracle::instruction::resolve_request(env.program_id, oracle.pubkey(), request);
+    env.send(&[resolve_ix], &[]).await.unwrap();
+    let state = env.request(request).await;
+    assert!(state.phase == Phase::Complete);
+    assert!(state.resolution_bit);
+}
+
+#[tokio::test]
+async fn test_request_slashes_pay_winning_ballots() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let program_id = env.program_id;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+    let oracle_key = oracle.pubkey();
+    let (winner, winner_authority) = env.join(oracle_key).await;
+    let (silent, silent_authority) = env.join(oracle_key).await;
+    let (colluder, colluder_authority) = env.join(oracle_key).await;
+
+    env.set_time(0).await;
+    let request = env.create_request(oracle_key, &authority).await;
+
+    // A leaked request vote is slashed just like one on the oracle's own round
+    env.commit_request(oracle_key, request, &colluder, &colluder_authority, false, [9u8; 32]).await;
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    let slash_ix = binary_oracle::instruction::slash_request_colluding(
+        program_id,
+        oracle_key,
+        request,
+        colluder,
+        ballot_address(&program_id, &request, &colluder),
+        slasher.pubkey(),
+        false,
+        [9u8; 32],
+    );
+    env.send(&[slash_ix], &[&slasher]).await.unwrap();
+    assert!(env.node(colluder).await.state == NodeState::Slashed);
+
+    // The slashed node no longer holds up the reveal window
+    env.commit_request(oracle_key, request, &winner, &winner_authority, true, [1u8; 32]).await;
+    env.commit_request(oracle_key, request, &silent, &silent_authority, true, [2u8; 32]).await;
+    assert!(env.request(request).await.phase == Phase::Reveal);
+    env.reveal_request(oracle_key, request, &winner, &winner_authority, true, [1u8; 32]).await;
+
+    // A ballot left unrevealed is slashed once the window closes
+    env.set_time(reveal_duration + 1).await;
+    let no_reveal_ix = binary_oracle::instruction::slash_request_no_reveal(
+        program_id,
+        oracle_key,
+        request,
+        silent,
+        ballot_address(&program_id, &request, &silent),
+    );
+    env.send(&[no_reveal_ix], &[]).await.unwrap();
+
+    let resolve_ix = binary_oracle::instruction::resolve_request(program_id, oracle_key, request);
+    env.send(&[resolve_ix], &[]).await.unwrap();
+    let state = env.request(request).await;
+    assert_eq!(state.slashed_nodes, 2);
+    assert_eq!(state.reward_per_node, 2 * collateral);
+
+    // The only winning ballot collects both slashed deposits, once
+    let claim = move |authority: &Keypair| {
+        binary_oracle::instruction::claim_ballot_reward(
+            program_id,
+            oracle_key,
+            request,
+            winner,
+            ballot_address(&program_id, &request, &winner),
+            authority.pubkey(),
+        )
+    };
+    let before = env.lamports(winner_authority.pubkey()).await;
+    env.send(&[claim(&winner_authority)], &[&winner_authority]).await.unwrap();
+    assert_eq!(env.lamports(winner_authority.pubkey()).await - before, 2 * collateral);
+    let result = env.send(&[claim(&winner_authority)], &[&winner_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+    assert_eq!(env.oracle(oracle_key).await.slashed_pool, 0);
+}
+
+#[tokio::test]
+async fn test_adjudication_slashes_wrong_reveals() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                adjudicated: true,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+    let votes = [true, false, false];
+    let mut nodes = Vec::new();
+    for _ in votes {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await;
+    }
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await.unwrap();
+    }
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+
+    // Not before the reveal window closes
+    let adjudicate = |true_bit: bool| {
+        with_remaining_accounts(
+            binary_oracle::instruction::slash_wrong_reveal(env.program_id, oracle.pubkey(), authority.pubkey(), true_bit),
+            &node_keys,
+        )
+    };
+    let early_ix = adjudicate(true);
+    let mismatch_ix = adjudicate(false);
+    let adjudicate_ix = adjudicate(true);
+    let result = env.send(&[early_ix], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealPhaseNotClosed);
+
+    env.set_time(reveal_duration + 1).await;
+    env.send(&[adjudicate_ix], &[&authority]).await.unwrap();
+    assert!(env.node(nodes[0].0).await.state == NodeState::Revealed);
+    assert!(env.node(nodes[1].0).await.state == NodeState::Slashed);
+    assert!(env.node(nodes[2].0).await.state == NodeState::Slashed);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, 2 * collateral);
+
+    let result = env.send(&[mismatch_ix], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::AdjudicationMismatch);
+
+    // The lone correct node takes the wrong majority's collateral
+    let before = env.lamports(nodes[0].0).await;
+    env.resolve(oracle.pubkey(), &authority, &node_keys).await.unwrap();
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.resolution_bit);
+    assert_eq!(env.lamports(nodes[0].0).await - before, 3 * collateral);
+}
+
+#[tokio::test]
+async fn test_overturned_wrong_reveal_slash_restores_revealed_node() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                adjudicated: true,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, false, [2u8; 32]).await.unwrap();
+
+    env.set_time(reveal_duration + 1).await;
+    let adjudicate_ix = with_remaining_accounts(
+        binary_oracle::instruction::slash_wrong_reveal(env.program_id, oracle.pubkey(), authority.pubkey(), true),
+        &[node1, node2],
+    );
+    env.send(&[adjudicate_ix], &[&authority]).await.unwrap();
+    let slashed = env.node(node2).await;
+    assert_eq!(slashed.slash_reason, Some(SlashReason::WrongReveal));
+    assert_eq!(slashed.slashed_amount, collateral);
+
+    // The node had already revealed, so that is the state it returns to
+    let ix = binary_oracle::instruction::overturn_slash(env.program_id, oracle.pubkey(), node2, authority.pubkey());
+    env.send(&[ix], &[&authority]).await.unwrap();
+    let reinstated = env.node(node2).await;
+    assert_eq!(reinstated.state, NodeState::Revealed);
+    assert_eq!(reinstated.slashed_by, None);
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.slashed_pool, 0);
+    assert_eq!(state.slashed_nodes, 0);
+    assert_eq!(state.slashed_this_round, 0);
+}
+
+#[tokio::test]
+async fn test_reveal_duration_is_capped() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+
+    for reveal_duration in [MAX_REVEAL_DURATION + 1, i64::MAX, -3600] {
+        let result = env.initialize_with(&authority, config(1_000_000, reveal_duration, 3)).await.map(|_| ());
+        assert_error(result, binary_oracle::ErrorCode::InvalidRevealDuration);
+    }
+
+    let oracle = env.initialize(&authority, 1_000_000, MAX_REVEAL_DURATION, 3).await;
+    assert_eq!(env.oracle(oracle.pubkey()).await.config.reveal_duration, MAX_REVEAL_DURATION);
+}
+
+#[tokio::test]
+async fn test_reveal_after_resolution_is_already_resolved() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                allow_minority_resolve: true,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let (revealer, revealer_authority) = env.join(oracle.pubkey()).await;
+    let (late, late_authority) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &revealer, &revealer_authority, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &late, &late_authority, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &revealer, &revealer_authority, true, [1u8; 32]).await.unwrap();
+
+    // Past the deadline the reveal fails before resolve runs ...
+    env.set_time(reveal_duration + 1).await;
+    let result = env.reveal(oracle.pubkey(), &late, &late_authority, true, [2u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::RevealPhaseClosed);
+
+    // ... and names the resolution once it has
+    env.resolve(oracle.pubkey(), &authority, &[revealer, late]).await.unwrap();
+    env.set_time(reveal_duration + 2).await;
+    let result = env.reveal(oracle.pubkey(), &late, &late_authority, true, [2u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyResolved);
+}
+
+#[tokio::test]
+async fn test_partial_slash_leaves_remainder_reclaimable() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                slash_bps: 5_000,
+                ..config(collateral, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    let (node3, authority3) = env.join(oracle.pubkey()).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &node3, &authority3, true, [3u8; 32]).await;
+    env.slash(oracle.pubkey(), node3, &authority1, true, [3u8; 32]).await.unwrap();
+    assert_eq!(env.node(node3).await.slashed_amount, collateral / 2);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, collateral / 2);
+
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await.unwrap();
+    let node1_before = env.lamports(node1).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2, node3]).await.unwrap();
+
+    // Only the seized half is shared out
+    assert_eq!(env.lamports(node1).await - node1_before, collateral + collateral / 4);
+
+    // The slashed node can't close before taking back the other half, and takes it once
+    let close_ix = binary_oracle::instruction::close_node(env.program_id, oracle.pubkey(), node3, authority3.pubkey());
+    let result = env.send(&[close_ix.clone()], &[&authority3]).await;
+    assert_error(result, binary_oracle::ErrorCode::UnclaimedFunds);
+
+    let reclaim_ix =
+        binary_oracle::instruction::reclaim_collateral(env.program_id, oracle.pubkey(), node3, authority3.pubkey());
+    let before = env.lamports(authority3.pubkey()).await;
+    env.send(&[reclaim_ix.clone()], &[&authority3]).await.unwrap();
+    assert_eq!(env.lamports(authority3.pubkey()).await - before, collateral / 2);
+
+    env.set_time(reveal_duration + 2).await;
+    let result = env.send(&[reclaim_ix], &[&authority3]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+    env.send(&[close_ix], &[&authority3]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_get_node_vote_after_resolution() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                allow_minority_resolve: true,
+                ..config(1_000_000, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+
+    let ix = binary_oracle::instruction::get_node_vote(env.program_id, oracle.pubkey(), node1);
+    let before: NodeVote = env.simulate(ix, &[]).await;
+    assert_eq!(before.vote, None);
+
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, false, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &node1, &authority1, false, [1u8; 32]).await.unwrap();
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[node1, node2]).await.unwrap();
+
+    let ix = binary_oracle::instruction::get_node_vote(env.program_id, oracle.pubkey(), node1);
+    let revealed: NodeVote = env.simulate(ix, &[]).await;
+    assert_eq!(revealed.vote, Some(false));
+    assert!(!revealed.slashed);
+    assert!(!revealed.claimed);
+
+    // A node that never revealed reads back without a vote
+    let ix = binary_oracle::instruction::get_node_vote(env.program_id, oracle.pubkey(), node2);
+    let silent: NodeVote = env.simulate(ix, &[]).await;
+    assert_eq!(silent.vote, None);
+}
+
+#[tokio::test]
+async fn test_losing_revealer_reclaims_collateral() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 3).await;
+    let votes = [true, true, false];
+    let mut nodes = Vec::new();
+    for _ in votes {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await;
+    }
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await.unwrap();
+    }
+    env.set_time(reveal_duration + 1).await;
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    env.resolve(oracle.pubkey(), &authority, &node_keys).await.unwrap();
+
+    let (loser, loser_authority) = &nodes[2];
+    let program_id = env.program_id;
+    let reclaim_ix =
+        binary_oracle::instruction::reclaim_collateral(program_id, oracle.pubkey(), *loser, loser_authority.pubkey());
+
+    // The minority node is owed no reward, so it may not close without taking its deposit back
+    let close_ix = binary_oracle::instruction::close_node(program_id, oracle.pubkey(), *loser, loser_authority.pubkey());
+    let result = env.send(&[close_ix.clone()], &[loser_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::UnclaimedFunds);
+    let claim_ix = binary_oracle::instruction::claim_reward(program_id, oracle.pubkey(), *loser, loser_authority.pubkey());
+    let result = env.send(&[claim_ix], &[loser_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::NotConsensusNode);
+
+    let before = env.lamports(loser_authority.pubkey()).await;
+    env.send(&[reclaim_ix.clone()], &[loser_authority]).await.unwrap();
+    assert_eq!(env.lamports(loser_authority.pubkey()).await - before, collateral);
+
+    env.set_time(reveal_duration + 2).await;
+    let result = env.send(&[reclaim_ix], &[loser_authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::AlreadyClaimed);
+    env.send(&[close_ix], &[loser_authority]).await.unwrap();
+}
+
+// Resolves a round where a node staking `large_stake` and one staking the collateral agree and a
+// third is slashed, returning what resolve paid the (large, small) nodes
+async fn resolve_with_reward_policy(reward_policy: RewardPolicy, large_stake: u64) -> (u64, u64) {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                reward_policy,
+                ..config(1_000_000, reveal_duration, 3)
+            },
+        )
+        .await
+        .unwrap();
+
+    let large = env.funded_keypair(10_000_000_000).await;
+    env.try_join_with_stake(oracle.pubkey(), &large, large_stake).await.unwrap();
+    let large_node = node_address(&env.program_id, &oracle.pubkey(), &large.pubkey());
+    let (small_node, small) = env.join(oracle.pubkey()).await;
+    let (colluder_node, colluder) = env.join(oracle.pubkey()).await;
+
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.set_time(0).await;
+    env.commit(oracle.pubkey(), &colluder_node, &colluder, false, [9u8; 32]).await;
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), colluder_node, &slasher, false, [9u8; 32]).await.unwrap();
+    env.commit(oracle.pubkey(), &large_node, &large, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &small_node, &small, true, [2u8; 32]).await;
+    env.reveal(oracle.pubkey(), &large_node, &large, true, [1u8; 32]).await.unwrap();
+    env.reveal(oracle.pubkey(), &small_node, &small, true, [2u8; 32]).await.unwrap();
+
+    let large_before = env.lamports(large_node).await;
+    let small_before = env.lamports(small_node).await;
+    env.set_time(reveal_duration + 1).await;
+    env.resolve(oracle.pubkey(), &authority, &[large_node, small_node]).await.unwrap();
+    (
+        env.lamports(large_node).await - large_before,
+        env.lamports(small_node).await - small_before,
+    )
+}
+
+#[tokio::test]
+async fn test_equal_split_reward_policy() {
+    let collateral = 1_000_000;
+    let (large, small) = resolve_with_reward_policy(RewardPolicy::EqualSplit, collateral).await;
+    assert_eq!(large, collateral + collateral / 2);
+    assert_eq!(small, collateral + collateral / 2);
+}
+
+#[tokio::test]
+async fn test_stake_proportional_reward_policy() {
+    let collateral = 1_000_000;
+    let (large, small) = resolve_with_reward_policy(RewardPolicy::StakeProportional, 3 * collateral).await;
+    assert_eq!(large, 3 * collateral + collateral * 3 / 4);
+    assert_eq!(small, collateral + collateral / 4);
+}
+
+#[tokio::test]
+async fn test_authority_drain_only_after_emergency_timeout() {
+    let mut env = TestEnv::new().await;
+    let authority = env.funded_keypair(10_000_000_000).await;
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 2).await;
+    let (node1, authority1) = env.join(oracle.pubkey()).await;
+    let (node2, authority2) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &node1, &authority1, true, [1u8; 32]).await;
+    env.commit(oracle.pubkey(), &node2, &authority2, true, [2u8; 32]).await;
+    let reveal_end_time = env.oracle(oracle.pubkey()).await.reveal_end_time;
+
+    let drain_ix = binary_oracle::instruction::authority_drain(env.program_id, oracle.pubkey(), authority.pubkey());
+    env.set_time(reveal_end_time + EMERGENCY_TIMEOUT - 1).await;
+    let result = env.send(&[drain_ix.clone()], &[&authority]).await;
+    assert_error(result, binary_oracle::ErrorCode::EmergencyTimeoutNotReached);
+
+    // Only the authority can pull the escape hatch
+    env.set_time(reveal_end_time + EMERGENCY_TIMEOUT).await;
+    let intruder = env.funded_keypair(1_000_000_000).await;
+    let intruder_ix = binary_oracle::instruction::authority_drain(env.program_id, oracle.pubkey(), intruder.pubkey());
+    assert!(env.send(&[intruder_ix], &[&intruder]).await.is_err());
+
+    let before = env.lamports(authority.pubkey()).await;
+    env.send(&[drain_ix], &[&authority]).await.unwrap();
+    assert_eq!(env.lamports(authority.pubkey()).await - before, 2 * collateral);
+    let state = env.oracle(oracle.pubkey()).await;
+    assert!(state.phase == Phase::Complete);
+}
+
+#[tokio::test]
+async fn test_slash_rejects_node_of_another_oracle() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, collateral, reveal_duration, 2).await;
+    let other = env.initialize(&authority, 10 * collateral, reveal_duration, 2).await;
+    env.join(oracle.pubkey()).await;
+    let (foreign_node, foreign_authority) = env.join(other.pubkey()).await;
+    env.join(other.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.start_request(other.pubkey(), &authority).await;
+    env.commit(other.pubkey(), &foreign_node, &foreign_authority, true, [1u8; 32]).await;
+
+    // The proof is valid for the node, but not against an oracle it never joined
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    let result = env.slash(oracle.pubkey(), foreign_node, &slasher, true, [1u8; 32]).await;
+    assert_error(result, binary_oracle::ErrorCode::NodeOracleMismatch);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, 0);
+
+    // Against its own oracle the whole deposit is seized
+    env.slash(other.pubkey(), foreign_node, &slasher, true, [1u8; 32]).await.unwrap();
+    assert_eq!(env.node(foreign_node).await.slashed_amount, 10 * collateral);
+    assert_eq!(env.oracle(other.pubkey()).await.slashed_pool, 10 * collateral);
+}
+
+#[tokio::test]
+async fn test_slash_seizes_the_nodes_actual_stake() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let collateral = 1_000_000;
+    let reveal_duration = 3600;
+    let oracle = env
+        .initialize_with(
+            &authority,
+            OracleConfig {
+                stake_weighted: true,
+                ..config(collateral, reveal_duration, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let large = env.funded_keypair(10_000_000_000).await;
+    env.try_join_with_stake(oracle.pubkey(), &large, 3 * collateral).await.unwrap();
+    let large_node = node_address(&env.program_id, &oracle.pubkey(), &large.pubkey());
+    env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &large_node, &large, true, [1u8; 32]).await;
+
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), large_node, &slasher, true, [1u8; 32]).await.unwrap();
+    assert_eq!(env.node(large_node).await.slashed_amount, 3 * collateral);
+    assert_eq!(env.oracle(oracle.pubkey()).await.slashed_pool, 3 * collateral);
+}
+
+#[tokio::test]
+async fn test_resolve_records_final_tallies() {
+    let mut env = TestEnv::new().await;
+    let authority = Keypair::new();
+    let reveal_duration = 3600;
+    let oracle = env.initialize(&authority, 1_000_000, reveal_duration, 4).await;
+    let votes = [true, true, false];
+    let mut nodes = Vec::new();
+    for _ in votes {
+        nodes.push(env.join(oracle.pubkey()).await);
+    }
+    let (colluder, colluder_authority) = env.join(oracle.pubkey()).await;
+    env.set_time(0).await;
+    env.start_request(oracle.pubkey(), &authority).await;
+    env.commit(oracle.pubkey(), &colluder, &colluder_authority, true, [9u8; 32]).await;
+    let slasher = env.funded_keypair(1_000_000_000).await;
+    env.slash(oracle.pubkey(), colluder, &slasher, true, [9u8; 32]).await.unwrap();
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.commit(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await;
+    }
+    for (i, ((node, node_authority), vote)) in nodes.iter().zip(votes).enumerate() {
+        env.reveal(oracle.pubkey(), node, node_authority, vote, [i as u8; 32]).await.unwrap();
+    }
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.slashed_nodes, 1);
+    assert_eq!((state.final_true_votes, state.final_false_votes, state.final_slashed), (0, 0, 0));
+
+    env.set_time(reveal_duration + 1).await;
+    let node_keys: Vec<Pubkey> = nodes.iter().map(|(node, _)| *node).collect();
+    env.resolve(oracle.pubkey(), &authority, &node_keys).await.unwrap();
+
+    let state = env.oracle(oracle.pubkey()).await;
+    assert_eq!(state.final_true_votes, 2);
+    assert_eq!(state.final_false_votes, 1);
+    assert_eq!(state.final_slashed, 1);
+}